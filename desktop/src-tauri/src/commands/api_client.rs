@@ -1,8 +1,19 @@
-use reqwest::Client;
+use std::time::Duration;
+
 use serde::{Deserialize, Serialize};
 
+use crate::commands::converter::ChannelInfo;
+use crate::commands::http::ApiClient;
+use crate::commands::probe::AudioProbe;
+use crate::commands::retry;
+use crate::commands::scanner::SequenceInfo;
+use crate::commands::timestamps::TimestampSource;
+use crate::commands::waveform::WaveformPeaks;
 use crate::error::AppError;
 
+/// How long a reachability probe waits for the server to answer
+const PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
 #[derive(Debug, Clone)]
 pub struct AuthHeaders {
     pub client_id: String,
@@ -16,8 +27,17 @@ pub struct PreflightFile {
     pub original_file_name: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub recorder_file_created_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recorder_file_created_at_source: Option<TimestampSource>,
     pub size_bytes: u64,
     pub sha256: String,
+    /// Duration, MIME type, codec, sample rate and channels of the file being uploaded
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    pub audio: Option<AudioProbe>,
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    pub sequence: Option<SequenceInfo>,
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    pub channel: Option<ChannelInfo>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -53,13 +73,32 @@ struct PresignRequest {
     expires_in_seconds: Option<u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     multipart: Option<bool>,
+    /// Base64 SHA-256 of the body, signed into the PUT URL
+    #[serde(skip_serializing_if = "Option::is_none")]
+    checksum_sha256: Option<String>,
+    /// Asks for per-part checksums on a multipart upload
+    #[serde(skip_serializing_if = "Option::is_none")]
+    checksum_algorithm: Option<&'static str>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChunkRegistration {
+    pub chunk_index: u32,
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub file_name: String,
+    pub sha256: String,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PresignResult {
     pub method: String,
+    /// Empty for a multipart upload, whose parts are presigned one by one
+    #[serde(default)]
     pub url: String,
+    #[serde(default)]
     pub headers: std::collections::HashMap<String, String>,
     pub upload_id: Option<String>,
 }
@@ -69,73 +108,94 @@ pub struct PresignResult {
 struct PresignPartRequest {
     upload_id: String,
     part_number: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    checksum_sha256: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
-struct PresignPartResponse {
-    url: String,
+pub struct PresignedPart {
+    pub url: String,
+    /// Headers that must accompany the PUT, such as the signed checksum
+    #[serde(default)]
+    pub headers: std::collections::HashMap<String, String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CompletedPart {
     pub part_number: u32,
+    #[serde(rename = "eTag", alias = "etag")]
+    pub etag: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checksum_sha256: Option<String>,
+}
+
+/// A part storage already holds for a multipart upload
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadedPart {
+    pub part_number: u32,
+    #[serde(rename = "eTag")]
     pub etag: String,
+    pub size: u64,
+    #[serde(default)]
+    pub checksum_sha256: Option<String>,
+}
+
+/// The server's record of an uploaded recording, as far as verifying the
+/// upload goes. The stored fields describe the object in storage and are
+/// recorded by `complete_upload`.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordingRecord {
+    pub status: String,
+    #[serde(default)]
+    pub stored_size_bytes: Option<u64>,
+    #[serde(default)]
+    pub stored_sha256: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ListPartsResponse {
+    parts: Vec<UploadedPart>,
 }
 
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
-struct CompleteMultipartRequest {
+struct AbortMultipartRequest {
     upload_id: String,
-    parts: Vec<CompletedPart>,
 }
 
-fn build_client(auth: &AuthHeaders) -> Result<Client, AppError> {
-    let mut headers = reqwest::header::HeaderMap::new();
-    if !auth.client_id.is_empty() {
-        headers.insert(
-            "Cf-Access-Client-Id",
-            auth.client_id.parse().map_err(|_| {
-                AppError::InvalidInput("Invalid client_id header value".into())
-            })?,
-        );
-        headers.insert(
-            "Cf-Access-Client-Secret",
-            auth.client_secret.parse().map_err(|_| {
-                AppError::InvalidInput("Invalid client_secret header value".into())
-            })?,
-        );
-    }
-
-    Client::builder()
-        .default_headers(headers)
-        .build()
-        .map_err(AppError::Http)
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CompleteMultipartRequest {
+    upload_id: String,
+    parts: Vec<CompletedPart>,
 }
 
 async fn check_response(res: reqwest::Response, context: &str) -> Result<reqwest::Response, AppError> {
     if !res.status().is_success() {
         let status = res.status().as_u16();
+        let retry_after = retry::retry_after(res.headers());
         let text = res.text().await.unwrap_or_default();
         return Err(AppError::Api {
             status,
             message: format!("{}: {}", context, text),
+            retry_after,
         });
     }
     Ok(res)
 }
 
 pub async fn preflight_batch(
-    server_url: String,
-    auth: AuthHeaders,
+    api: &ApiClient,
     batch_id: String,
     files: Vec<PreflightFile>,
 ) -> Result<Vec<PreflightResult>, AppError> {
-    let client = build_client(&auth)?;
-    let url = format!("{}/api/v1/recordings/preflight-batch", server_url.trim_end_matches('/'));
+    let url = api.url("/api/v1/recordings/preflight-batch");
 
     let body = PreflightBatchRequest { batch_id, files };
-    let res = client.post(&url).json(&body).send().await?;
+    let res = api.api().post(&url).json(&body).send().await?;
     let res = check_response(res, "preflight-batch").await?;
     let response: PreflightBatchResponse = res.json().await?;
 
@@ -143,95 +203,167 @@ pub async fn preflight_batch(
 }
 
 pub async fn presign(
-    server_url: String,
-    auth: AuthHeaders,
+    api: &ApiClient,
     recording_id: String,
     upload_id: String,
     multipart: Option<bool>,
+    checksum_sha256: Option<String>,
 ) -> Result<PresignResult, AppError> {
-    let client = build_client(&auth)?;
-    let url = format!(
-        "{}/api/v1/recordings/{}/presign",
-        server_url.trim_end_matches('/'),
-        recording_id
-    );
+    let url = api.url(&format!("/api/v1/recordings/{}/presign", recording_id));
 
+    let checksum_algorithm = (multipart == Some(true)).then_some("SHA256");
     let body = PresignRequest {
         upload_id,
         expires_in_seconds: None,
         multipart,
+        checksum_sha256,
+        checksum_algorithm,
     };
-    let res = client.post(&url).json(&body).send().await?;
+    let res = api.api().post(&url).json(&body).send().await?;
     let res = check_response(res, "presign").await?;
 
     Ok(res.json().await?)
 }
 
 pub async fn presign_part(
-    server_url: String,
-    auth: AuthHeaders,
+    api: &ApiClient,
     recording_id: String,
     upload_id: String,
     part_number: u32,
-) -> Result<String, AppError> {
-    let client = build_client(&auth)?;
-    let url = format!(
-        "{}/api/v1/recordings/{}/presign-part",
-        server_url.trim_end_matches('/'),
-        recording_id
-    );
+    checksum_sha256: Option<String>,
+) -> Result<PresignedPart, AppError> {
+    let url = api.url(&format!("/api/v1/recordings/{}/presign-part", recording_id));
 
     let body = PresignPartRequest {
         upload_id,
         part_number,
+        checksum_sha256,
     };
-    let res = client.post(&url).json(&body).send().await?;
+    let res = api.api().post(&url).json(&body).send().await?;
     let res = check_response(res, "presign-part").await?;
-    let response: PresignPartResponse = res.json().await?;
 
-    Ok(response.url)
+    Ok(res.json().await?)
+}
+
+/// Record a transcription chunk for a recording and get a presigned PUT URL for it
+pub async fn register_chunk(
+    api: &ApiClient,
+    recording_id: String,
+    chunk: ChunkRegistration,
+) -> Result<PresignResult, AppError> {
+    let url = api.url(&format!("/api/v1/recordings/{}/chunks", recording_id));
+
+    let res = api.api().post(&url).json(&chunk).send().await?;
+    let res = check_response(res, "register-chunk").await?;
+
+    Ok(res.json().await?)
 }
 
 pub async fn complete_multipart(
-    server_url: String,
-    auth: AuthHeaders,
+    api: &ApiClient,
     recording_id: String,
     upload_id: String,
     parts: Vec<CompletedPart>,
 ) -> Result<(), AppError> {
-    let client = build_client(&auth)?;
-    let url = format!(
-        "{}/api/v1/recordings/{}/complete-multipart",
-        server_url.trim_end_matches('/'),
-        recording_id
-    );
+    let url = api.url(&format!("/api/v1/recordings/{}/complete-multipart", recording_id));
 
     let body = CompleteMultipartRequest { upload_id, parts };
-    let res = client.post(&url).json(&body).send().await?;
+    let res = api.api().post(&url).json(&body).send().await?;
     check_response(res, "complete-multipart").await?;
 
     Ok(())
 }
 
-#[allow(dead_code)]
+/// Abort a multipart upload and discard its stored parts. Aborting an upload
+/// that no longer exists succeeds.
+pub async fn abort_multipart(
+    api: &ApiClient,
+    recording_id: String,
+    upload_id: String,
+) -> Result<(), AppError> {
+    let url = api.url(&format!("/api/v1/recordings/{}/abort-multipart", recording_id));
+
+    let body = AbortMultipartRequest { upload_id };
+    let res = api.api().post(&url).json(&body).send().await?;
+    check_response(res, "abort-multipart").await?;
+
+    Ok(())
+}
+
+/// Parts already stored for a multipart upload. A 404 means the upload no
+/// longer exists, because it was completed or aborted.
+pub async fn list_parts(
+    api: &ApiClient,
+    recording_id: String,
+    upload_id: String,
+) -> Result<Vec<UploadedPart>, AppError> {
+    let url = api.url(&format!("/api/v1/recordings/{}/parts", recording_id));
+
+    let res = api
+        .api()
+        .get(&url)
+        .query(&[("uploadId", upload_id)])
+        .send()
+        .await?;
+    let res = check_response(res, "list-parts").await?;
+    let response: ListPartsResponse = res.json().await?;
+
+    Ok(response.parts)
+}
+
+/// Store a recording's waveform peaks so the web page can draw it without decoding
+pub async fn upload_waveform(
+    api: &ApiClient,
+    recording_id: String,
+    peaks: &WaveformPeaks,
+) -> Result<(), AppError> {
+    let url = api.url(&format!("/api/v1/recordings/{}/waveform", recording_id));
+
+    let res = api.api().put(&url).json(peaks).send().await?;
+    check_response(res, "upload-waveform").await?;
+
+    Ok(())
+}
+
+/// Tell the server the raw upload is in place, which has it record the size
+/// and hash of what storage holds
 pub async fn complete_upload(
-    server_url: String,
-    auth: AuthHeaders,
+    api: &ApiClient,
     recording_id: String,
 ) -> Result<(), AppError> {
-    let client = build_client(&auth)?;
-    let url = format!(
-        "{}/api/v1/recordings/{}/complete",
-        server_url.trim_end_matches('/'),
-        recording_id
-    );
-
-    let res = client.post(&url).send().await?;
+    let url = api.url(&format!("/api/v1/recordings/{}/complete", recording_id));
+
+    let res = api.api().post(&url).send().await?;
     check_response(res, "complete-upload").await?;
 
     Ok(())
 }
 
+pub async fn get_recording(
+    api: &ApiClient,
+    recording_id: String,
+) -> Result<RecordingRecord, AppError> {
+    let url = api.url(&format!("/api/v1/recordings/{}", recording_id));
+
+    let res = api.api().get(&url).send().await?;
+    let res = check_response(res, "get-recording").await?;
+
+    Ok(res.json().await?)
+}
+
+/// Check that the server can be reached, returning the status it answered
+/// with. Any HTTP response will do, since only reachability matters here.
+pub async fn probe_server(api: &ApiClient) -> Result<u16, AppError> {
+    let res = api
+        .api()
+        .head(api.url(""))
+        .timeout(PROBE_TIMEOUT)
+        .send()
+        .await?;
+
+    Ok(res.status().as_u16())
+}
+
 // Implement Deserialize for AuthHeaders so it can be used in Tauri commands
 impl<'de> Deserialize<'de> for AuthHeaders {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
@@ -0,0 +1,151 @@
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+use chrono::{Local, NaiveTime};
+use log::info;
+use serde::Serialize;
+
+use crate::config::{BandwidthConfig, BandwidthWindow};
+use crate::error::AppError;
+
+/// Longest sleep between checks for a changed limit while a transfer waits
+const WAIT_SLICE: Duration = Duration::from_millis(200);
+/// Unused allowance that may build up while idle, as time at the limit
+const BURST: Duration = Duration::from_millis(500);
+
+/// Shared by every upload, so the cap holds for all transfers together
+static LIMITER: LazyLock<Mutex<Limiter>> = LazyLock::new(|| {
+    Mutex::new(Limiter {
+        config: BandwidthConfig::default(),
+        override_mbps: None,
+        allowance: 0.0,
+        refilled: Instant::now(),
+        generation: 0,
+    })
+});
+
+/// Token bucket holding the allowance of bytes that may be sent now. It may
+/// go negative: a transfer takes its bytes at once and then waits out the debt.
+struct Limiter {
+    config: BandwidthConfig,
+    /// Set at runtime through `set_bandwidth_limit`, in force until cleared
+    override_mbps: Option<f64>,
+    allowance: f64,
+    refilled: Instant,
+    /// Bumped whenever the settings change, releasing transfers that wait
+    generation: u64,
+}
+
+impl Limiter {
+    /// Cap in force now, in Mbit/s; 0 means none
+    fn limit_mbps(&self) -> f64 {
+        self.override_mbps.unwrap_or_else(|| {
+            let now = Local::now().time();
+            self.config
+                .schedule
+                .iter()
+                .find(|window| window_contains(window, now))
+                .map_or(self.config.limit_mbps, |window| window.limit_mbps)
+        })
+    }
+
+    fn changed(&mut self) {
+        self.allowance = 0.0;
+        self.refilled = Instant::now();
+        self.generation += 1;
+    }
+}
+
+/// Whether `now` falls in the window; windows ending before they start run
+/// past midnight. Windows with unreadable times never match.
+fn window_contains(window: &BandwidthWindow, now: NaiveTime) -> bool {
+    let parse = |time: &str| NaiveTime::parse_from_str(time, "%H:%M").ok();
+    match (parse(&window.start), parse(&window.end)) {
+        (Some(start), Some(end)) if start <= end => start <= now && now < end,
+        (Some(start), Some(end)) => now >= start || now < end,
+        _ => false,
+    }
+}
+
+fn bytes_per_sec(mbps: f64) -> f64 {
+    mbps * 1_000_000.0 / 8.0
+}
+
+/// Apply saved bandwidth settings to transfers already running and to come
+pub fn configure(config: &BandwidthConfig) {
+    let mut limiter = LIMITER.lock().unwrap();
+    limiter.config = config.clone();
+    limiter.changed();
+}
+
+/// Wait until `bytes` more may be sent under the cap in force
+pub async fn throttle(bytes: usize) {
+    let (mut wait, generation) = {
+        let mut limiter = LIMITER.lock().unwrap();
+        let mbps = limiter.limit_mbps();
+        if mbps <= 0.0 {
+            return;
+        }
+        let rate = bytes_per_sec(mbps);
+        let now = Instant::now();
+        let elapsed = now.duration_since(limiter.refilled).as_secs_f64();
+        limiter.allowance = (limiter.allowance + elapsed * rate).min(rate * BURST.as_secs_f64());
+        limiter.refilled = now;
+        limiter.allowance -= bytes as f64;
+        if limiter.allowance >= 0.0 {
+            return;
+        }
+        (
+            Duration::from_secs_f64(-limiter.allowance / rate),
+            limiter.generation,
+        )
+    };
+
+    while !wait.is_zero() {
+        let slice = wait.min(WAIT_SLICE);
+        tokio::time::sleep(slice).await;
+        wait -= slice;
+        if LIMITER.lock().unwrap().generation != generation {
+            return;
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BandwidthStatus {
+    /// Cap in force now, in Mbit/s; 0 means none
+    pub limit_mbps: f64,
+    /// Runtime cap set through `set_bandwidth_limit`, overriding settings
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub override_mbps: Option<f64>,
+}
+
+fn status(limiter: &Limiter) -> BandwidthStatus {
+    BandwidthStatus {
+        limit_mbps: limiter.limit_mbps(),
+        override_mbps: limiter.override_mbps,
+    }
+}
+
+#[tauri::command]
+pub fn get_bandwidth_status() -> BandwidthStatus {
+    status(&LIMITER.lock().unwrap())
+}
+
+/// Throttle or unthrottle uploads until told otherwise: `limit_mbps` of 0
+/// lifts the cap, and no value returns to the saved settings and schedule.
+/// Not saved, so a restart also returns to the settings.
+#[tauri::command]
+pub fn set_bandwidth_limit(limit_mbps: Option<f64>) -> Result<BandwidthStatus, AppError> {
+    if limit_mbps.is_some_and(|mbps| !mbps.is_finite() || mbps < 0.0) {
+        return Err(AppError::InvalidInput(
+            "Bandwidth limit must be zero or more".to_string(),
+        ));
+    }
+    let mut limiter = LIMITER.lock().unwrap();
+    limiter.override_mbps = limit_mbps;
+    limiter.changed();
+    info!("Upload bandwidth limit set to {:?} Mbit/s", limit_mbps);
+    Ok(status(&limiter))
+}
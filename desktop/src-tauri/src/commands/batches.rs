@@ -30,7 +30,7 @@ pub async fn clean_completed_batches(
         batches_to_remove = app_state
             .batches
             .iter()
-            .filter(|(_, batch)| batch.files.values().all(|f| f.uploaded))
+            .filter(|(_, batch)| batch.files.values().all(|f| f.is_done()))
             .map(|(id, _)| id.clone())
             .collect();
     }
@@ -1,5 +1,8 @@
+use tauri::State;
 use tauri_plugin_store::StoreExt;
 
+use crate::commands::bandwidth;
+use crate::commands::upload_queue::ManagedQueue;
 use crate::config::{read_config, write_config, AppConfig};
 use crate::error::AppError;
 
@@ -9,8 +12,11 @@ pub async fn get_config() -> Result<AppConfig, AppError> {
 }
 
 #[tauri::command]
-pub async fn save_config(config: AppConfig) -> Result<(), AppError> {
-    write_config(&config).await
+pub async fn save_config(config: AppConfig, queue: State<'_, ManagedQueue>) -> Result<(), AppError> {
+    write_config(&config).await?;
+    bandwidth::configure(&config.bandwidth);
+    queue.set_concurrency(config.upload_concurrency);
+    Ok(())
 }
 
 #[derive(serde::Serialize)]
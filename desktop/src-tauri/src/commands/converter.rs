@@ -1,13 +1,20 @@
 use std::path::Path;
+use std::process::Stdio;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use log::warn;
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, BufReader};
 use tokio::process::Command;
 
+use crate::commands::probe::AudioProbe;
+use crate::config::{read_config, ChannelMode, ConversionProfile};
 use crate::error::AppError;
 
-const LARGE_WAV_THRESHOLD: u64 = 50 * 1024 * 1024; // 50MB
-
 /// Build an extended PATH that includes common Homebrew/system locations.
 /// macOS GUI apps don't inherit the user's shell PATH, so we add them explicitly.
-fn extended_path() -> String {
+pub(crate) fn extended_path() -> String {
     let base = std::env::var("PATH").unwrap_or_default();
     let extras = ["/opt/homebrew/bin", "/usr/local/bin", "/usr/bin"];
     let mut parts: Vec<&str> = extras.to_vec();
@@ -17,6 +24,18 @@ fn extended_path() -> String {
     parts.join(":")
 }
 
+/// ffprobe ships alongside ffmpeg, so derive its path from the configured ffmpeg binary.
+pub(crate) fn ffprobe_path(ffmpeg_path: &str) -> String {
+    let path = Path::new(ffmpeg_path);
+    match path.file_name().and_then(|n| n.to_str()) {
+        Some(name) if name.starts_with("ffmpeg") => path
+            .with_file_name(name.replacen("ffmpeg", "ffprobe", 1))
+            .to_string_lossy()
+            .to_string(),
+        _ => "ffprobe".to_string(),
+    }
+}
+
 #[tauri::command]
 pub async fn check_ffmpeg(ffmpeg_path: Option<String>) -> Result<bool, AppError> {
     let ffmpeg = ffmpeg_path.unwrap_or_else(|| "ffmpeg".to_string());
@@ -67,45 +86,491 @@ pub async fn detect_ffmpeg_path() -> Result<Option<String>, AppError> {
     Ok(None)
 }
 
-#[tauri::command]
-pub fn needs_conversion(file_name: String, file_size: u64) -> bool {
-    let ext = Path::new(&file_name)
+/// Whether any of the profile's triggers matches this file's extension and size.
+pub fn profile_applies(profile: &ConversionProfile, file_name: &str, file_size: u64) -> bool {
+    let ext = Path::new(file_name)
         .extension()
         .and_then(|e| e.to_str())
-        .map(|e| e.to_lowercase())
         .unwrap_or_default();
 
-    match ext.as_str() {
-        "wma" => true,
-        "wav" => file_size > LARGE_WAV_THRESHOLD,
-        _ => false,
-    }
+    profile
+        .triggers
+        .iter()
+        .any(|t| t.format.eq_ignore_ascii_case(ext) && file_size >= t.min_size_mb * 1024 * 1024)
+}
+
+#[tauri::command]
+pub async fn needs_conversion(
+    file_name: String,
+    file_size: u64,
+    device_id: Option<String>,
+) -> Result<bool, AppError> {
+    let config = read_config().await?;
+    let profile = config.conversion_profile_for(device_id.as_deref().unwrap_or_default());
+    Ok(profile_applies(&profile, &file_name, file_size))
 }
 
+/// Convert with a profile. `channel` (0-based) extracts a single source channel
+/// as mono, which is how a `Split` profile produces each of its recordings.
 #[tauri::command]
 pub async fn convert_audio(
     input: String,
     output: String,
     ffmpeg_path: Option<String>,
-) -> Result<(), AppError> {
+    profile: Option<ConversionProfile>,
+    channel: Option<u16>,
+) -> Result<Option<LoudnessStats>, AppError> {
     let ffmpeg = ffmpeg_path.unwrap_or_else(|| "ffmpeg".to_string());
+    let profile = match profile {
+        Some(profile) => profile,
+        None => read_config().await?.conversion_profile_for(""),
+    };
+    let cancel = AtomicBool::new(false);
+    let job = Job {
+        input: &input,
+        output: &output,
+        channel,
+    };
+    convert_job(job, &ffmpeg, &profile, None, &cancel, &mut |_| {}).await
+}
 
-    let result = Command::new(&ffmpeg)
-        .args([
-            "-y", "-i", &input, "-ac", "1", "-ar", "16000", "-b:a", "64k", &output,
-        ])
+/// Whether the profile splits this source into one recording per channel
+pub fn splits_channels(profile: &ConversionProfile, source: Option<&AudioProbe>) -> bool {
+    profile.channel_mode() == ChannelMode::Split
+        && source.and_then(|a| a.channels).is_some_and(|c| c > 1)
+}
+
+/// Links the mono recordings split from one multi-channel source.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChannelInfo {
+    /// Shared by all channels; derived from the source's hash so re-imports agree
+    pub channel_group_id: String,
+    /// 1-based, in source order (1 = left for stereo)
+    pub channel_index: u32,
+    pub channel_count: u32,
+}
+
+/// Progress parsed from ffmpeg's `-progress` output.
+#[derive(Debug, Clone, Default)]
+pub struct FfmpegProgress {
+    /// Position in the output, in milliseconds of audio
+    pub out_time_ms: u64,
+    /// Only known when the input duration is
+    pub percent: Option<f64>,
+    /// Encoding speed as a multiple of real time
+    pub speed: Option<f64>,
+    pub eta_seconds: Option<u64>,
+}
+
+/// EBU R128 measurement of the source, taken by the first `loudnorm` pass.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LoudnessStats {
+    pub integrated_lufs: f64,
+    pub true_peak_dbtp: f64,
+    pub loudness_range_lu: f64,
+    pub threshold_lufs: f64,
+    /// Gain correction loudnorm applies on top of the linear adjustment
+    pub target_offset_lu: f64,
+    pub target_lufs: f64,
+}
+
+/// Encode `input` into `output` (whose extension should be `profile.container`),
+/// reporting progress through `on_progress`. Setting `cancel` kills ffmpeg and
+/// removes the partial output; the call then returns `AppError::Cancelled`.
+///
+/// With loudness normalization enabled, the source is measured in a first pass
+/// and normalized linearly in the second; each pass covers half the reported
+/// progress. The measurement is returned and written to the output's tags.
+pub async fn convert_with_profile(
+    input: &str,
+    output: &str,
+    ffmpeg: &str,
+    profile: &ConversionProfile,
+    source: Option<&AudioProbe>,
+    cancel: &AtomicBool,
+    mut on_progress: impl FnMut(&FfmpegProgress),
+) -> Result<Option<LoudnessStats>, AppError> {
+    let job = Job {
+        input,
+        output,
+        channel: None,
+    };
+    convert_job(job, ffmpeg, profile, source, cancel, &mut on_progress).await
+}
+
+/// Encode each channel of `input` into its own mono file, `outputs[i]` holding
+/// channel `i`. Channels are converted, and loudness-normalized, one after the
+/// other; progress covers all of them. On failure or cancellation every output
+/// is removed.
+pub async fn split_with_profile(
+    input: &str,
+    outputs: &[String],
+    ffmpeg: &str,
+    profile: &ConversionProfile,
+    source: Option<&AudioProbe>,
+    cancel: &AtomicBool,
+    mut on_progress: impl FnMut(&FfmpegProgress),
+) -> Result<Vec<Option<LoudnessStats>>, AppError> {
+    let count = outputs.len().max(1) as f64;
+    let mut stats = Vec::with_capacity(outputs.len());
+    for (i, output) in outputs.iter().enumerate() {
+        let job = Job {
+            input,
+            output,
+            channel: Some(i as u16),
+        };
+        let result = convert_job(
+            job,
+            ffmpeg,
+            profile,
+            source,
+            cancel,
+            &mut |p: &FfmpegProgress| {
+                on_progress(&FfmpegProgress {
+                    percent: p.percent.map(|pct| (i as f64 * 100.0 + pct) / count),
+                    ..p.clone()
+                })
+            },
+        )
+        .await;
+        match result {
+            Ok(loudness) => stats.push(loudness),
+            Err(e) => {
+                for done in &outputs[..i] {
+                    let _ = tokio::fs::remove_file(done).await;
+                }
+                return Err(e);
+            }
+        }
+    }
+    Ok(stats)
+}
+
+/// One ffmpeg conversion: the whole source, or a single channel of it
+struct Job<'a> {
+    input: &'a str,
+    output: &'a str,
+    /// 0-based source channel to extract as mono
+    channel: Option<u16>,
+}
+
+async fn convert_job(
+    job: Job<'_>,
+    ffmpeg: &str,
+    profile: &ConversionProfile,
+    source: Option<&AudioProbe>,
+    cancel: &AtomicBool,
+    on_progress: &mut impl FnMut(&FfmpegProgress),
+) -> Result<Option<LoudnessStats>, AppError> {
+    let Job {
+        input,
+        output,
+        channel,
+    } = job;
+    let duration_ms = source.and_then(|a| a.duration_ms);
+    let Some(target) = profile.preprocess.loudness_target_lufs else {
+        let args = profile_args(&job, profile, None, source);
+        run_ffmpeg(ffmpeg, args, Some(output), duration_ms, cancel, on_progress).await?;
+        return Ok(None);
+    };
+
+    let mut measure_args: Vec<String> = vec!["-i".into(), input.into(), "-vn".into()];
+    let mut measure_filters = filter_chain(profile, None, channel);
+    if let Some(last) = measure_filters.last_mut() {
+        last.push_str(":print_format=json");
+    }
+    measure_args.extend(["-af".into(), measure_filters.join(",")]);
+    // Measure what will be encoded: the downmix changes loudness
+    if let Some(channels) = output_channels(profile, channel) {
+        measure_args.extend(["-ac".into(), channels.to_string()]);
+    }
+    measure_args.extend(["-f".into(), "null".into(), "-".into()]);
+    let stderr = run_ffmpeg(
+        ffmpeg,
+        measure_args,
+        None,
+        duration_ms,
+        cancel,
+        &mut |p: &FfmpegProgress| {
+            on_progress(&FfmpegProgress {
+                percent: p.percent.map(|pct| pct / 2.0),
+                // assume the second pass runs at the same speed
+                eta_seconds: p.eta_seconds.zip(duration_ms.zip(p.speed)).map(
+                    |(eta, (total, speed))| eta + (total as f64 / 1000.0 / speed).round() as u64,
+                ),
+                ..p.clone()
+            })
+        },
+    )
+    .await?;
+
+    let stats = match parse_loudnorm_json(&stderr, target) {
+        Some(stats) => Some(stats),
+        None => {
+            // e.g. digital silence measures as -inf; fall back to one-pass normalization
+            warn!("Could not read loudness measurement of {}", input);
+            None
+        }
+    };
+
+    let args = profile_args(&job, profile, stats.as_ref(), source);
+    run_ffmpeg(
+        ffmpeg,
+        args,
+        Some(output),
+        duration_ms,
+        cancel,
+        &mut |p: &FfmpegProgress| {
+            on_progress(&FfmpegProgress {
+                percent: p.percent.map(|pct| 50.0 + pct / 2.0),
+                ..p.clone()
+            })
+        },
+    )
+    .await?;
+    Ok(stats)
+}
+
+/// Run ffmpeg with `-progress` reporting, returning its stderr. `output` is
+/// removed if the run fails or is cancelled.
+async fn run_ffmpeg(
+    ffmpeg: &str,
+    args: Vec<String>,
+    output: Option<&str>,
+    duration_ms: Option<u64>,
+    cancel: &AtomicBool,
+    on_progress: &mut impl FnMut(&FfmpegProgress),
+) -> Result<String, AppError> {
+    let mut child = Command::new(ffmpeg)
+        .args(["-nostats", "-progress", "pipe:1"])
+        .args(args)
         .env("PATH", extended_path())
-        .output()
-        .await?;
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()?;
+
+    // Drain stderr concurrently so a chatty ffmpeg can't block on a full pipe
+    let mut stderr = child.stderr.take().expect("stderr is piped");
+    let stderr_task = tokio::spawn(async move {
+        let mut buf = String::new();
+        let _ = stderr.read_to_string(&mut buf).await;
+        buf
+    });
+
+    let mut lines = BufReader::new(child.stdout.take().expect("stdout is piped")).lines();
+    let mut progress = FfmpegProgress::default();
+    let mut ticker = tokio::time::interval(Duration::from_millis(250));
+    loop {
+        tokio::select! {
+            line = lines.next_line() => match line? {
+                Some(line) => {
+                    if apply_progress_line(&mut progress, &line, duration_ms) {
+                        on_progress(&progress);
+                    }
+                }
+                None => break,
+            },
+            _ = ticker.tick() => {
+                if cancel.load(Ordering::Relaxed) {
+                    let _ = child.kill().await;
+                    if let Some(output) = output {
+                        let _ = tokio::fs::remove_file(output).await;
+                    }
+                    return Err(AppError::Cancelled);
+                }
+            }
+        }
+    }
 
-    if !result.status.success() {
-        let stderr = String::from_utf8_lossy(&result.stderr);
+    let status = child.wait().await?;
+    let stderr = stderr_task.await.unwrap_or_default();
+    if !status.success() {
+        if let Some(output) = output {
+            let _ = tokio::fs::remove_file(output).await;
+        }
         return Err(AppError::Ffmpeg(format!(
             "ffmpeg conversion failed (exit {}): {}",
-            result.status.code().unwrap_or(-1),
+            status.code().unwrap_or(-1),
             stderr
         )));
     }
 
-    Ok(())
+    Ok(stderr)
+}
+
+/// Pull the JSON block loudnorm prints at the end of stderr.
+fn parse_loudnorm_json(stderr: &str, target_lufs: f64) -> Option<LoudnessStats> {
+    let start = stderr.rfind('{')?;
+    let end = stderr[start..].find('}')? + start;
+    let json: serde_json::Value = serde_json::from_str(&stderr[start..=end]).ok()?;
+    // loudnorm reports every value as a string
+    let field = |key: &str| -> Option<f64> {
+        json.get(key)?
+            .as_str()?
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .filter(|v| v.is_finite())
+    };
+    Some(LoudnessStats {
+        integrated_lufs: field("input_i")?,
+        true_peak_dbtp: field("input_tp")?,
+        loudness_range_lu: field("input_lra")?,
+        threshold_lufs: field("input_thresh")?,
+        target_offset_lu: field("target_offset")?,
+        target_lufs,
+    })
+}
+
+/// Fold one `key=value` line of `-progress` output into `progress`. Returns true
+/// at the end of each report block (`progress=continue|end`).
+fn apply_progress_line(
+    progress: &mut FfmpegProgress,
+    line: &str,
+    duration_ms: Option<u64>,
+) -> bool {
+    let Some((key, value)) = line.trim().split_once('=') else {
+        return false;
+    };
+    match key {
+        // Despite the name, `out_time_ms` is in microseconds, like `out_time_us`
+        "out_time_us" | "out_time_ms" => {
+            if let Ok(us) = value.parse::<i64>() {
+                progress.out_time_ms = us.max(0) as u64 / 1000;
+            }
+        }
+        "speed" => {
+            progress.speed = value
+                .trim_end_matches('x')
+                .trim()
+                .parse::<f64>()
+                .ok()
+                .filter(|s| *s > 0.0);
+        }
+        "progress" => {
+            let done = value == "end";
+            if let Some(total) = duration_ms.filter(|d| *d > 0) {
+                let position = if done {
+                    total
+                } else {
+                    progress.out_time_ms.min(total)
+                };
+                progress.percent = Some(position as f64 * 100.0 / total as f64);
+                progress.eta_seconds = if done {
+                    Some(0)
+                } else {
+                    progress
+                        .speed
+                        .map(|speed| ((total - position) as f64 / 1000.0 / speed).round() as u64)
+                };
+            }
+            return true;
+        }
+        _ => {}
+    }
+    false
+}
+
+/// The extracted channel, pre-processing filters, then the profile's own, then
+/// loudnorm (which must come last so nothing changes the level after it).
+fn filter_chain(
+    profile: &ConversionProfile,
+    measured: Option<&LoudnessStats>,
+    channel: Option<u16>,
+) -> Vec<String> {
+    let pre = &profile.preprocess;
+    let mut filters = Vec::new();
+    // First, so the gate and loudnorm only hear the one microphone
+    if let Some(channel) = channel {
+        filters.push(format!("pan=mono|c0=c{}", channel));
+    }
+    if let Some(hz) = pre.highpass_hz {
+        filters.push(format!("highpass=f={}", hz));
+    }
+    if let Some(hz) = pre.hum_hz {
+        // Mains hum and its first harmonics
+        for harmonic in 1..=3 {
+            filters.push(format!("bandreject=f={}:width_type=q:w=30", hz * harmonic));
+        }
+    }
+    if let Some(db) = pre.gate_threshold_db {
+        filters.push(format!(
+            "agate=threshold={:.6}:attack=10:release=250",
+            10f64.powf(db / 20.0)
+        ));
+    }
+    filters.extend(profile.filters.iter().cloned());
+    if let Some(target) = pre.loudness_target_lufs {
+        let mut loudnorm = format!(
+            "loudnorm=I={}:TP={}:LRA={}",
+            target, pre.true_peak_dbtp, pre.loudness_range_lu
+        );
+        if let Some(m) = measured {
+            loudnorm.push_str(&format!(
+                ":measured_I={}:measured_TP={}:measured_LRA={}:measured_thresh={}:offset={}:linear=true",
+                m.integrated_lufs,
+                m.true_peak_dbtp,
+                m.loudness_range_lu,
+                m.threshold_lufs,
+                m.target_offset_lu
+            ));
+        }
+        filters.push(loudnorm);
+    }
+    filters
+}
+
+/// `-ac` for the output; a single extracted channel is already mono
+fn output_channels(profile: &ConversionProfile, channel: Option<u16>) -> Option<u16> {
+    match (profile.channel_mode(), channel) {
+        (_, Some(_)) => None,
+        (ChannelMode::Downmix, None) => Some(profile.channels.unwrap_or(1)),
+        (ChannelMode::Keep | ChannelMode::Split, None) => None,
+    }
+}
+
+fn profile_args(
+    job: &Job<'_>,
+    profile: &ConversionProfile,
+    measured: Option<&LoudnessStats>,
+    source: Option<&AudioProbe>,
+) -> Vec<String> {
+    let mut args: Vec<String> = vec!["-y".into(), "-i".into(), job.input.into(), "-vn".into()];
+    let filters = filter_chain(profile, measured, job.channel);
+    if !filters.is_empty() {
+        args.extend(["-af".into(), filters.join(",")]);
+    }
+    if let Some(channels) = output_channels(profile, job.channel) {
+        args.extend(["-ac".into(), channels.to_string()]);
+    }
+    // loudnorm resamples to 192 kHz internally; restore the source rate
+    let sample_rate = profile.sample_rate.or_else(|| {
+        profile
+            .preprocess
+            .loudness_target_lufs
+            .and(source.and_then(|a| a.sample_rate))
+    });
+    if let Some(rate) = sample_rate {
+        args.extend(["-ar".into(), rate.to_string()]);
+    }
+    args.extend(["-c:a".into(), profile.codec.clone()]);
+    if let Some(kbps) = profile.bitrate_kbps {
+        args.extend(["-b:a".into(), format!("{}k", kbps)]);
+    }
+    if let Some(m) = measured {
+        for (key, value) in [
+            ("LOUDNESS_INTEGRATED_LUFS", m.integrated_lufs),
+            ("LOUDNESS_TRUE_PEAK_DBTP", m.true_peak_dbtp),
+            ("LOUDNESS_RANGE_LU", m.loudness_range_lu),
+            ("LOUDNESS_TARGET_LUFS", m.target_lufs),
+        ] {
+            args.extend(["-metadata".into(), format!("{}={}", key, value)]);
+        }
+    }
+    args.push(job.output.into());
+    args
 }
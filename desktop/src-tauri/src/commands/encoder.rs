@@ -0,0 +1,476 @@
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Seek, SeekFrom, Take, Write};
+use std::path::Path;
+
+use flacenc::bitsink::ByteSink;
+use flacenc::component::{BitRepr, Stream};
+use flacenc::config::Encoder;
+use flacenc::error::{Verified, Verify};
+use flacenc::source::{Context, Fill, FrameBuf};
+use rubato::{FftFixedIn, Resampler};
+use shine_rs::{Mp3Encoder, Mp3EncoderConfig, StereoMode};
+
+use crate::commands::stitcher::read_wav_part;
+use crate::config::NativeEncoding;
+use crate::error::AppError;
+
+const BLOCK_SIZE: usize = 4096;
+/// Rate used by the compact encoding; plenty for speech transcription
+const COMPACT_SAMPLE_RATE: usize = 16_000;
+/// Bit rate of the compact encoding, the same as the ffmpeg speech profile
+const COMPACT_BITRATE_KBPS: u32 = 64;
+const RESAMPLER_CHUNK: usize = 1024;
+
+/// Encode a PCM WAV file without ffmpeg.
+///
+/// `Lossless` writes FLAC at the original rate, channels and bit depth.
+/// `Compact` downmixes to mono, resamples to 16 kHz and writes 64 kbit/s MP3,
+/// the same as the ffmpeg speech profile: about 1/20 the size of 16-bit 48 kHz
+/// stereo. Inputs that are not PCM/float WAV fail with `InvalidInput`.
+///
+/// With `channel` (0-based) set, only that channel is encoded, as mono.
+pub async fn encode_wav(
+    input: &str,
+    output: &str,
+    encoding: NativeEncoding,
+    channel: Option<usize>,
+) -> Result<(), AppError> {
+    let input_path = input.to_string();
+    let output_path = output.to_string();
+    let result = tokio::task::spawn_blocking(move || {
+        let reader = WavReader::open(Path::new(&input_path))?;
+        if let Some(channel) = channel.filter(|c| *c >= reader.channels) {
+            return Err(AppError::InvalidInput(format!(
+                "{} has no channel {}",
+                input_path,
+                channel + 1
+            )));
+        }
+        match encoding {
+            NativeEncoding::Lossless => encode_lossless(reader, Path::new(&output_path), channel),
+            NativeEncoding::Compact => encode_compact(reader, Path::new(&output_path), channel),
+        }
+    })
+    .await
+    .map_err(|e| AppError::Other(e.to_string()))?;
+
+    if result.is_err() {
+        let _ = tokio::fs::remove_file(output).await;
+    }
+    result
+}
+
+fn encode_lossless(
+    mut reader: WavReader,
+    output: &Path,
+    channel: Option<usize>,
+) -> Result<(), AppError> {
+    if reader.float || reader.bits() > 24 {
+        return Err(AppError::InvalidInput(format!(
+            "{}-bit {} WAV can't be stored losslessly as FLAC",
+            reader.bytes_per_sample * 8,
+            if reader.float { "float" } else { "integer" }
+        )));
+    }
+
+    let channels = reader.channels;
+    let out_channels = if channel.is_some() { 1 } else { channels };
+    let mut writer = FlacWriter::create(output, reader.sample_rate, out_channels, reader.bits())?;
+    let mut samples = Vec::new();
+    let mut picked = Vec::new();
+    while reader.read_frames(BLOCK_SIZE, &mut samples)? > 0 {
+        match channel {
+            Some(c) => {
+                picked.clear();
+                picked.extend(samples.chunks_exact(channels).map(|frame| frame[c]));
+                writer.push(&picked)?;
+            }
+            None => writer.push(&samples)?,
+        }
+    }
+    writer.finish()
+}
+
+fn encode_compact(
+    mut reader: WavReader,
+    output: &Path,
+    channel: Option<usize>,
+) -> Result<(), AppError> {
+    let mut writer = Mp3Writer::create(output, COMPACT_SAMPLE_RATE)?;
+    let mut resampler = if reader.sample_rate == COMPACT_SAMPLE_RATE {
+        None
+    } else {
+        Some(
+            FftFixedIn::<f32>::new(
+                reader.sample_rate,
+                COMPACT_SAMPLE_RATE,
+                RESAMPLER_CHUNK,
+                2,
+                1,
+            )
+            .map_err(|e| AppError::Other(format!("Resampler setup failed: {}", e)))?,
+        )
+    };
+
+    let scale = 1.0 / (1u32 << (reader.bits() - 1)) as f32;
+    let channels = reader.channels;
+    let mut samples = Vec::new();
+    let mut mono: Vec<f32> = Vec::new();
+    let mut frames_in = 0u64;
+    // The resampler's output starts `output_delay` frames late; drop those
+    let mut skip = resampler.as_ref().map_or(0, |r| r.output_delay());
+    let mut emitted = 0u64;
+
+    loop {
+        let read = reader.read_frames(BLOCK_SIZE, &mut samples)?;
+        frames_in += read as u64;
+        mono.extend(samples.chunks_exact(channels).map(|frame| match channel {
+            Some(c) => frame[c] as f32 * scale,
+            None => frame.iter().map(|&s| s as f32).sum::<f32>() * scale / channels as f32,
+        }));
+
+        match resampler.as_mut() {
+            None => {
+                writer.push(&quantize(&mono))?;
+                emitted += mono.len() as u64;
+                mono.clear();
+            }
+            Some(resampler) => {
+                while mono.len() >= resampler.input_frames_next() {
+                    let needed = resampler.input_frames_next();
+                    let out = resampler
+                        .process(&[&mono[..needed]], None)
+                        .map_err(|e| AppError::Other(format!("Resampling failed: {}", e)))?;
+                    mono.drain(..needed);
+                    emit_resampled(&mut writer, &out[0], &mut skip, &mut emitted, u64::MAX)?;
+                }
+            }
+        }
+        if read == 0 {
+            break;
+        }
+    }
+
+    if let Some(resampler) = resampler.as_mut() {
+        // Flush the remaining input, then zeros until the delayed tail is out
+        let expected = frames_in * COMPACT_SAMPLE_RATE as u64 / reader.sample_rate as u64;
+        let out = resampler
+            .process_partial(Some(&[&mono]), None)
+            .map_err(|e| AppError::Other(format!("Resampling failed: {}", e)))?;
+        emit_resampled(&mut writer, &out[0], &mut skip, &mut emitted, expected)?;
+        while emitted < expected {
+            let out = resampler
+                .process_partial::<&[f32]>(None, None)
+                .map_err(|e| AppError::Other(format!("Resampling failed: {}", e)))?;
+            emit_resampled(&mut writer, &out[0], &mut skip, &mut emitted, expected)?;
+        }
+    }
+
+    writer.finish()
+}
+
+fn emit_resampled(
+    writer: &mut Mp3Writer,
+    out: &[f32],
+    skip: &mut usize,
+    emitted: &mut u64,
+    limit: u64,
+) -> Result<(), AppError> {
+    let dropped = (*skip).min(out.len());
+    *skip -= dropped;
+    let remaining = limit
+        .saturating_sub(*emitted)
+        .min((out.len() - dropped) as u64) as usize;
+    let out = &out[dropped..dropped + remaining];
+    writer.push(&quantize(out))?;
+    *emitted += out.len() as u64;
+    Ok(())
+}
+
+fn quantize(samples: &[f32]) -> Vec<i16> {
+    samples
+        .iter()
+        .map(|&s| (s * 32767.0).round().clamp(-32768.0, 32767.0) as i16)
+        .collect()
+}
+
+fn mp3_error(e: impl std::fmt::Display) -> AppError {
+    AppError::Other(format!("MP3 encoding failed: {}", e))
+}
+
+/// Streams mono MP3 frames to disk as the encoder fills them
+struct Mp3Writer {
+    file: BufWriter<File>,
+    encoder: Mp3Encoder,
+}
+
+impl Mp3Writer {
+    fn create(path: &Path, sample_rate: usize) -> Result<Self, AppError> {
+        let config = Mp3EncoderConfig::new()
+            .sample_rate(sample_rate as u32)
+            .bitrate(COMPACT_BITRATE_KBPS)
+            .channels(1)
+            .stereo_mode(StereoMode::Mono);
+        Ok(Self {
+            file: BufWriter::new(File::create(path)?),
+            encoder: Mp3Encoder::new(config).map_err(mp3_error)?,
+        })
+    }
+
+    fn push(&mut self, samples: &[i16]) -> Result<(), AppError> {
+        // The encoder refuses empty input
+        if samples.is_empty() {
+            return Ok(());
+        }
+        for frame in self
+            .encoder
+            .encode_interleaved(samples)
+            .map_err(mp3_error)?
+        {
+            self.file.write_all(&frame)?;
+        }
+        Ok(())
+    }
+
+    /// Encode the last partial frame, padded with silence, and flush
+    fn finish(mut self) -> Result<(), AppError> {
+        let tail = self.encoder.finish().map_err(mp3_error)?;
+        self.file.write_all(&tail)?;
+        self.file
+            .into_inner()
+            .map_err(|e| AppError::Io(e.into_error()))?
+            .sync_all()?;
+        Ok(())
+    }
+}
+
+fn flac_error(e: impl std::fmt::Display) -> AppError {
+    AppError::Other(format!("FLAC encoding failed: {}", e))
+}
+
+/// Streams fixed-size FLAC frames to disk; STREAMINFO is rewritten on `finish`
+/// once the sample count and MD5 are known.
+struct FlacWriter {
+    file: BufWriter<File>,
+    stream: Stream,
+    config: Verified<Encoder>,
+    framebuf: FrameBuf,
+    context: Context,
+    pending: Vec<i32>,
+    sink: ByteSink,
+    channels: usize,
+}
+
+impl FlacWriter {
+    fn create(
+        path: &Path,
+        sample_rate: usize,
+        channels: usize,
+        bits_per_sample: usize,
+    ) -> Result<Self, AppError> {
+        let mut stream = Stream::new(sample_rate, channels, bits_per_sample).map_err(flac_error)?;
+        stream
+            .stream_info_mut()
+            .set_block_sizes(BLOCK_SIZE, BLOCK_SIZE)
+            .map_err(flac_error)?;
+        let config = Encoder::default()
+            .into_verified()
+            .map_err(|(_, e)| flac_error(e))?;
+
+        let mut writer = Self {
+            file: BufWriter::new(File::create(path)?),
+            stream,
+            config,
+            framebuf: FrameBuf::with_size(channels, BLOCK_SIZE).map_err(flac_error)?,
+            context: Context::new(bits_per_sample, channels),
+            pending: Vec::with_capacity(BLOCK_SIZE * channels),
+            sink: ByteSink::new(),
+            channels,
+        };
+        // Placeholder header; same length as the final one
+        writer.write_header()?;
+        Ok(writer)
+    }
+
+    /// Queue interleaved samples, encoding every full block.
+    fn push(&mut self, samples: &[i32]) -> Result<(), AppError> {
+        self.pending.extend_from_slice(samples);
+        let block_len = BLOCK_SIZE * self.channels;
+        while self.pending.len() >= block_len {
+            self.encode_block(block_len)?;
+        }
+        Ok(())
+    }
+
+    fn encode_block(&mut self, len: usize) -> Result<(), AppError> {
+        let block = &self.pending[..len];
+        self.framebuf.fill_interleaved(block).map_err(flac_error)?;
+        self.context.fill_interleaved(block).map_err(flac_error)?;
+        let frame_number = self.context.current_frame_number().unwrap_or(0);
+        let frame = flacenc::encode_fixed_size_frame(
+            &self.config,
+            &self.framebuf,
+            frame_number,
+            self.stream.stream_info(),
+        )
+        .map_err(flac_error)?;
+        self.stream.stream_info_mut().update_frame_info(&frame);
+
+        self.sink.clear();
+        frame.write(&mut self.sink).map_err(flac_error)?;
+        self.file.write_all(self.sink.as_slice())?;
+        self.pending.drain(..len);
+        Ok(())
+    }
+
+    fn write_header(&mut self) -> Result<(), AppError> {
+        self.sink.clear();
+        self.stream.write(&mut self.sink).map_err(flac_error)?;
+        self.file.write_all(self.sink.as_slice())?;
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<(), AppError> {
+        if !self.pending.is_empty() {
+            self.encode_block(self.pending.len())?;
+        }
+        let md5 = self.context.md5_digest();
+        let total_samples = self.context.total_samples();
+        let info = self.stream.stream_info_mut();
+        info.set_md5_digest(&md5);
+        info.set_total_samples(total_samples);
+        // update_frame_info lowers the minimum for the short final frame; like libFLAC,
+        // keep min == max so decoders treat the stream as fixed-blocksize
+        info.set_block_sizes(BLOCK_SIZE, BLOCK_SIZE)
+            .map_err(flac_error)?;
+
+        self.file.seek(SeekFrom::Start(0))?;
+        self.write_header()?;
+        self.file
+            .into_inner()
+            .map_err(|e| AppError::Io(e.into_error()))?
+            .sync_all()?;
+        Ok(())
+    }
+}
+
+/// Sequential reader over the data chunk of a PCM or IEEE-float WAV file.
+struct WavReader {
+    data: BufReader<Take<File>>,
+    channels: usize,
+    sample_rate: usize,
+    bytes_per_sample: usize,
+    float: bool,
+    buf: Vec<u8>,
+}
+
+impl WavReader {
+    fn open(path: &Path) -> Result<Self, AppError> {
+        let part = read_wav_part(path)?;
+        let fmt = &part.fmt;
+        if fmt.len() < 16 {
+            return Err(AppError::InvalidInput("Truncated fmt chunk".into()));
+        }
+        let mut format_tag = u16::from_le_bytes([fmt[0], fmt[1]]);
+        if format_tag == 0xFFFE && fmt.len() >= 26 {
+            // WAVE_FORMAT_EXTENSIBLE: the real format is the start of the subformat GUID
+            format_tag = u16::from_le_bytes([fmt[24], fmt[25]]);
+        }
+        let channels = u16::from_le_bytes([fmt[2], fmt[3]]) as usize;
+        let sample_rate = u32::from_le_bytes([fmt[4], fmt[5], fmt[6], fmt[7]]) as usize;
+        let block_align = u16::from_le_bytes([fmt[12], fmt[13]]) as usize;
+        let bytes_per_sample = block_align.checked_div(channels).unwrap_or(0);
+
+        let float = match (format_tag, bytes_per_sample) {
+            (1, 1..=4) => false,
+            (3, 4 | 8) => true,
+            _ => {
+                return Err(AppError::InvalidInput(format!(
+                    "{} is not PCM audio the built-in encoder can read",
+                    path.display()
+                )))
+            }
+        };
+        if channels == 0 || channels > 8 || sample_rate == 0 {
+            return Err(AppError::InvalidInput(format!(
+                "{} has an unsupported layout ({} channels, {} Hz)",
+                path.display(),
+                channels,
+                sample_rate
+            )));
+        }
+
+        let mut file = File::open(path)?;
+        file.seek(SeekFrom::Start(part.data_start))?;
+        Ok(Self {
+            data: BufReader::new(file.take(part.data_size)),
+            channels,
+            sample_rate,
+            bytes_per_sample,
+            float,
+            buf: Vec::new(),
+        })
+    }
+
+    /// Bit depth of the samples returned by `read_frames`; float input is scaled to 24-bit.
+    fn bits(&self) -> usize {
+        if self.float {
+            24
+        } else {
+            self.bytes_per_sample * 8
+        }
+    }
+
+    /// Read up to `frames` frames as interleaved signed integers, returning the
+    /// number of whole frames read (0 at the end of the data).
+    fn read_frames(&mut self, frames: usize, out: &mut Vec<i32>) -> Result<usize, AppError> {
+        let frame_bytes = self.bytes_per_sample * self.channels;
+        self.buf.resize(frames * frame_bytes, 0);
+        let mut filled = 0;
+        while filled < self.buf.len() {
+            let n = self.data.read(&mut self.buf[filled..])?;
+            if n == 0 {
+                break;
+            }
+            filled += n;
+        }
+        let read = filled / frame_bytes;
+
+        out.clear();
+        let bytes = &self.buf[..read * frame_bytes];
+        match (self.bytes_per_sample, self.float) {
+            // 8-bit WAV is unsigned
+            (1, _) => out.extend(bytes.iter().map(|&b| b as i32 - 128)),
+            (2, _) => out.extend(
+                bytes
+                    .chunks_exact(2)
+                    .map(|b| i16::from_le_bytes([b[0], b[1]]) as i32),
+            ),
+            (3, _) => out.extend(
+                bytes
+                    .chunks_exact(3)
+                    .map(|b| i32::from_le_bytes([0, b[0], b[1], b[2]]) >> 8),
+            ),
+            (4, false) => out.extend(
+                bytes
+                    .chunks_exact(4)
+                    .map(|b| i32::from_le_bytes([b[0], b[1], b[2], b[3]])),
+            ),
+            (4, true) => out.extend(
+                bytes
+                    .chunks_exact(4)
+                    .map(|b| float_to_24(f32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64)),
+            ),
+            _ => out.extend(
+                bytes
+                    .chunks_exact(8)
+                    .map(|b| float_to_24(f64::from_le_bytes(b.try_into().unwrap()))),
+            ),
+        }
+        Ok(read)
+    }
+}
+
+fn float_to_24(s: f64) -> i32 {
+    (s * 8_388_607.0).round().clamp(-8_388_608.0, 8_388_607.0) as i32
+}
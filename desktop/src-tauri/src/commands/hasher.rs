@@ -1,3 +1,4 @@
+use base64::Engine;
 use sha2::{Digest, Sha256};
 use tauri::{AppHandle, Emitter};
 use tokio::fs;
@@ -103,3 +104,39 @@ pub async fn copy_with_hash(
     let hash = format!("{:x}", hasher.finalize());
     Ok(hash)
 }
+
+/// Base64 SHA-256 of an in-memory payload, the form S3 expects in
+/// `x-amz-checksum-sha256`
+pub fn sha256_base64(data: &[u8]) -> String {
+    base64::engine::general_purpose::STANDARD.encode(Sha256::digest(data))
+}
+
+/// Re-encode a hex SHA-256 digest as base64 for checksum headers
+pub fn hex_to_base64(hex: &str) -> Option<String> {
+    if hex.len() != 64 || !hex.is_ascii() {
+        return None;
+    }
+    let bytes = (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect::<Option<Vec<u8>>>()?;
+    Some(base64::engine::general_purpose::STANDARD.encode(bytes))
+}
+
+/// Composite checksum of a multipart upload from its parts' base64 SHA-256
+/// checksums in part order: the SHA-256 of the digests concatenated, as hex
+/// with the part count appended. This is what the server records for an
+/// object assembled from parts.
+pub fn multipart_sha256(part_checksums: &[&str]) -> Option<String> {
+    let mut hasher = Sha256::new();
+    for checksum in part_checksums {
+        let digest = base64::engine::general_purpose::STANDARD
+            .decode(checksum)
+            .ok()?;
+        if digest.len() != 32 {
+            return None;
+        }
+        hasher.update(&digest);
+    }
+    Some(format!("{:x}-{}", hasher.finalize(), part_checksums.len()))
+}
@@ -0,0 +1,269 @@
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+use log::{info, warn};
+use reqwest::header::HeaderMap;
+use reqwest::{Certificate, Client, ClientBuilder, NoProxy, Proxy};
+
+use crate::commands::api_client::AuthHeaders;
+use crate::config::{get_base_path, AppConfig, HttpConfig};
+use crate::error::AppError;
+
+/// Identifies this installation in the user agent; generated on first use
+static DEVICE_ID: LazyLock<String> = LazyLock::new(load_device_id);
+
+/// Connections to one server profile. Clones share the connection pools, so
+/// requests reuse open connections instead of dialing the server each time.
+#[derive(Clone)]
+pub struct ApiClient {
+    server_url: String,
+    /// Sends the access headers, for the server's API
+    api: Client,
+    /// Sends no access headers, for presigned storage URLs
+    storage: Client,
+    stall_timeout: Duration,
+}
+
+impl ApiClient {
+    /// Absolute URL of a server path
+    pub fn url(&self, path: &str) -> String {
+        format!("{}{}", self.server_url.trim_end_matches('/'), path)
+    }
+
+    pub fn api(&self) -> &Client {
+        &self.api
+    }
+
+    pub fn storage(&self) -> &Client {
+        &self.storage
+    }
+
+    /// Longest an upload may go without its body moving
+    pub fn stall_timeout(&self) -> Duration {
+        self.stall_timeout
+    }
+}
+
+/// What a client was built for; a change to any of it means a new client
+#[derive(PartialEq)]
+struct Profile {
+    server_url: String,
+    client_id: String,
+    client_secret: String,
+    http: HttpConfig,
+}
+
+/// The long-lived client of the configured server, held in managed state
+#[derive(Default)]
+pub struct HttpClients {
+    current: Mutex<Option<(Profile, ApiClient)>>,
+}
+
+pub type ManagedHttp = Arc<HttpClients>;
+
+impl HttpClients {
+    /// Client for the configured server, reused until the server URL, the
+    /// credentials or the HTTP settings change
+    pub fn client(&self, config: &AppConfig, auth: &AuthHeaders) -> Result<ApiClient, AppError> {
+        let profile = Profile {
+            server_url: config.server_url.clone(),
+            client_id: auth.client_id.clone(),
+            client_secret: auth.client_secret.clone(),
+            http: config.http.clone(),
+        };
+        let mut current = self.current.lock().unwrap();
+        if let Some((built_for, client)) = current.as_ref() {
+            if *built_for == profile {
+                return Ok(client.clone());
+            }
+        }
+
+        info!("Building HTTP client for {}", profile.server_url);
+        let client = build(&profile)?;
+        *current = Some((profile, client.clone()));
+        Ok(client)
+    }
+}
+
+fn build(profile: &Profile) -> Result<ApiClient, AppError> {
+    let http = &profile.http;
+    let read_timeout = Duration::from_secs(http.read_timeout_secs.max(1));
+    let api = builder(http)?
+        .default_headers(access_headers(&profile.client_id, &profile.client_secret)?)
+        .read_timeout(read_timeout)
+        .build()?;
+    // No read timeout here: reqwest counts it from the start of the request,
+    // which would cut off any upload that takes longer. `watch_stall` covers
+    // uploads instead.
+    let storage = builder(http)?.build()?;
+
+    Ok(ApiClient {
+        server_url: profile.server_url.clone(),
+        api,
+        storage,
+        stall_timeout: read_timeout,
+    })
+}
+
+/// Settings shared by the API and storage clients, so uploads take the same
+/// proxy and trust the same CAs as API calls
+fn builder(http: &HttpConfig) -> Result<ClientBuilder, AppError> {
+    let keepalive = (http.keepalive_secs > 0).then(|| Duration::from_secs(http.keepalive_secs));
+    let mut builder = Client::builder()
+        .user_agent(user_agent())
+        .connect_timeout(Duration::from_secs(http.connect_timeout_secs.max(1)))
+        .pool_idle_timeout(Duration::from_secs(http.pool_idle_secs))
+        .tcp_keepalive(keepalive);
+    builder = match http.http2 {
+        true => builder.http2_adaptive_window(true),
+        false => builder.http1_only(),
+    };
+
+    if let Some(proxy) = proxy(http)? {
+        builder = builder.proxy(proxy);
+    }
+    for path in &http.ca_certificates {
+        for certificate in read_certificates(path)? {
+            builder = builder.add_root_certificate(certificate);
+        }
+    }
+    Ok(builder)
+}
+
+/// The configured proxy, or `None` to use the system's
+fn proxy(http: &HttpConfig) -> Result<Option<Proxy>, AppError> {
+    let Some(url) = http
+        .proxy_url
+        .as_deref()
+        .map(str::trim)
+        .filter(|u| !u.is_empty())
+    else {
+        return Ok(None);
+    };
+    let proxy = Proxy::all(url)
+        .map_err(|e| AppError::InvalidInput(format!("Invalid proxy URL {}: {}", url, e)))?;
+    Ok(Some(
+        proxy.no_proxy(NoProxy::from_string(&http.no_proxy.join(","))),
+    ))
+}
+
+/// Certificates of a PEM file, which may hold a whole chain
+fn read_certificates(path: &str) -> Result<Vec<Certificate>, AppError> {
+    let pem = std::fs::read(path).map_err(|e| {
+        AppError::InvalidInput(format!("Could not read CA certificate {}: {}", path, e))
+    })?;
+    let certificates = Certificate::from_pem_bundle(&pem)
+        .map_err(|e| AppError::InvalidInput(format!("Invalid CA certificate {}: {}", path, e)))?;
+    if certificates.is_empty() {
+        return Err(AppError::InvalidInput(format!(
+            "No certificates found in {}",
+            path
+        )));
+    }
+    Ok(certificates)
+}
+
+/// Cloudflare Access service token headers, when a token is configured
+fn access_headers(client_id: &str, client_secret: &str) -> Result<HeaderMap, AppError> {
+    let mut headers = HeaderMap::new();
+    if !client_id.is_empty() {
+        headers.insert(
+            "Cf-Access-Client-Id",
+            client_id
+                .parse()
+                .map_err(|_| AppError::InvalidInput("Invalid client_id header value".into()))?,
+        );
+        headers.insert(
+            "Cf-Access-Client-Secret",
+            client_secret
+                .parse()
+                .map_err(|_| AppError::InvalidInput("Invalid client_secret header value".into()))?,
+        );
+    }
+    Ok(headers)
+}
+
+fn user_agent() -> String {
+    format!(
+        "VoiceTrunk-Desktop/{} ({}; device {})",
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::OS,
+        *DEVICE_ID
+    )
+}
+
+/// Read this installation's ID, creating it the first time. An ID that
+/// cannot be saved still serves this session.
+fn load_device_id() -> String {
+    let path = get_base_path().join("device-id");
+    if let Ok(id) = std::fs::read_to_string(&path) {
+        let id = id.trim();
+        if !id.is_empty() {
+            return id.to_string();
+        }
+    }
+
+    let id = uuid::Uuid::new_v4().to_string();
+    let saved = std::fs::create_dir_all(get_base_path()).and_then(|_| std::fs::write(&path, &id));
+    if let Err(e) = saved {
+        warn!("Could not save device ID: {}", e);
+    }
+    id
+}
+
+/// When a transfer last made progress, to tell a slow upload from a stalled one
+#[derive(Clone)]
+pub struct Activity {
+    started: Instant,
+    /// Milliseconds after `started`
+    last: Arc<AtomicU64>,
+}
+
+impl Default for Activity {
+    fn default() -> Self {
+        Self {
+            started: Instant::now(),
+            last: Arc::new(AtomicU64::new(0)),
+        }
+    }
+}
+
+impl Activity {
+    pub fn touch(&self) {
+        let now = self.started.elapsed().as_millis() as u64;
+        self.last.store(now, Ordering::Relaxed);
+    }
+
+    fn idle(&self) -> Duration {
+        self.started
+            .elapsed()
+            .saturating_sub(Duration::from_millis(self.last.load(Ordering::Relaxed)))
+    }
+}
+
+/// Run a transfer, failing it with `AppError::Timeout` once `activity` has
+/// not been touched for `limit`
+pub async fn watch_stall<T>(
+    activity: &Activity,
+    limit: Duration,
+    transfer: impl Future<Output = Result<T, AppError>>,
+) -> Result<T, AppError> {
+    let stalled = async {
+        loop {
+            let idle = activity.idle();
+            if idle >= limit {
+                break;
+            }
+            tokio::time::sleep(limit - idle).await;
+        }
+    };
+    tokio::select! {
+        result = transfer => result,
+        _ = stalled => Err(AppError::Timeout(format!(
+            "no progress for {} seconds",
+            limit.as_secs()
+        ))),
+    }
+}
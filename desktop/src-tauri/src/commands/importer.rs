@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 use std::path::Path;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::sync::Arc;
 
 use chrono::Utc;
@@ -9,20 +9,76 @@ use tauri::{AppHandle, Emitter, State};
 use tauri_plugin_store::StoreExt;
 
 use crate::commands::api_client::{self, AuthHeaders, PreflightFile};
-use crate::commands::converter;
+use crate::commands::converter::{self, ChannelInfo, LoudnessStats};
+use crate::commands::encoder;
 use crate::commands::hasher;
-use crate::commands::scanner;
+use crate::commands::http::{ApiClient, HttpClients, ManagedHttp};
+use crate::commands::network;
+use crate::commands::probe::{self, AudioProbe};
+use crate::commands::retry;
+use crate::commands::scanner::{self, SequenceCandidate, SequenceInfo};
+use crate::commands::splitter::{self, ChunkInfo};
+use crate::commands::stitcher;
+use crate::commands::timestamps::{self, RecordingTimestamp, TimestampSource};
+use crate::commands::upload_queue::{ManagedQueue, UploadJob, UploadPriority, UploadQueue};
 use crate::commands::uploader;
-use crate::config::{get_inbox_path, read_config};
+use crate::commands::vad;
+use crate::commands::validator::{self, ValidationOutcome};
+use crate::commands::volumes;
+use crate::commands::waveform::{self, WaveformPeaks};
+use crate::config::{get_inbox_path, read_config, AppConfig, MultipartConfig, RetryConfig, SequenceMode};
 use crate::error::AppError;
-use crate::events::ImportProgress;
-use crate::state::{persist_state, BatchState, BatchStatus, FileStatus, ManagedState, ManagedStateInner};
+use crate::events::{ConversionProgress, ImportProgress};
+use crate::state::{
+    persist_state, BatchState, BatchStatus, FileStatus, HeldUpload, ManagedState, ManagedStateInner,
+};
 
 /// Global cancellation flags, keyed by batch_id
 static CANCEL_FLAGS: std::sync::LazyLock<
     std::sync::Mutex<HashMap<String, Arc<AtomicBool>>>,
 > = std::sync::LazyLock::new(|| std::sync::Mutex::new(HashMap::new()));
 
+/// A batch's cancellation flag, registered while work on the batch runs so
+/// `cancel_import` can reach it. Joins the flag of a batch that is already
+/// running, and only removes a flag it added itself.
+struct CancelRegistration {
+    batch_id: String,
+    flag: Arc<AtomicBool>,
+    owned: bool,
+}
+
+impl CancelRegistration {
+    fn register(batch_id: &str) -> Self {
+        let mut flags = CANCEL_FLAGS.lock().unwrap();
+        let (flag, owned) = match flags.get(batch_id) {
+            Some(flag) => (flag.clone(), false),
+            None => {
+                let flag = Arc::new(AtomicBool::new(false));
+                flags.insert(batch_id.to_string(), flag.clone());
+                (flag, true)
+            }
+        };
+        Self {
+            batch_id: batch_id.to_string(),
+            flag,
+            owned,
+        }
+    }
+}
+
+impl Drop for CancelRegistration {
+    fn drop(&mut self) {
+        if self.owned {
+            CANCEL_FLAGS.lock().unwrap().remove(&self.batch_id);
+        }
+    }
+}
+
+/// Whether work on the batch is running in this session
+pub(crate) fn is_batch_active(batch_id: &str) -> bool {
+    CANCEL_FLAGS.lock().unwrap().contains_key(batch_id)
+}
+
 fn generate_batch_id() -> String {
     let now = Utc::now();
     let ts = now.format("%Y%m%d%H%M%S").to_string();
@@ -48,6 +104,56 @@ fn rand_char() -> char {
     chars[(val % chars.len() as u64) as usize] as char
 }
 
+/// Read the Cloudflare Access service token from the credentials store
+pub(crate) fn read_auth(app_handle: &AppHandle) -> AuthHeaders {
+    match app_handle.store("credentials.json") {
+        Ok(store) => {
+            let client_id = store
+                .get("clientId")
+                .and_then(|v| v.as_str().map(|s| s.to_string()))
+                .unwrap_or_default();
+            let client_secret = store
+                .get("clientSecret")
+                .and_then(|v| v.as_str().map(|s| s.to_string()))
+                .unwrap_or_default();
+            AuthHeaders {
+                client_id,
+                client_secret,
+            }
+        }
+        Err(_) => AuthHeaders {
+            client_id: String::new(),
+            client_secret: String::new(),
+        },
+    }
+}
+
+/// Derive a batch's status from its files once processing has finished.
+/// Batches without any files are left as they are.
+fn finalize_batch_status(managed_state: &ManagedStateInner, batch_id: &str) -> Option<BatchStatus> {
+    let mut app_state = managed_state.inner.lock().unwrap();
+    let batch = app_state.batches.get_mut(batch_id)?;
+    if batch.files.is_empty() {
+        return None;
+    }
+    let has_errors = batch.files.values().any(|f| f.error.is_some());
+    let all_done = batch.files.values().all(|f| f.is_done());
+    let has_held = batch.files.values().any(|f| f.held.is_some());
+    let has_waiting = batch.files.values().any(|f| f.waiting_for_network.is_some());
+    batch.status = if all_done {
+        BatchStatus::Completed
+    } else if has_waiting {
+        BatchStatus::WaitingForNetwork
+    } else if has_errors {
+        BatchStatus::PartialError
+    } else if has_held {
+        BatchStatus::AwaitingReview
+    } else {
+        BatchStatus::Uploading
+    };
+    Some(batch.status.clone())
+}
+
 /// Check if the inbox has room for additional bytes
 async fn check_storage_limit(additional_bytes: u64) -> Result<bool, AppError> {
     let config = read_config().await?;
@@ -81,41 +187,16 @@ pub async fn start_import(
     device_id: String,
     app_handle: AppHandle,
     state: State<'_, ManagedState>,
+    queue: State<'_, ManagedQueue>,
+    http: State<'_, ManagedHttp>,
 ) -> Result<String, AppError> {
     let config = read_config().await?;
     let batch_id = generate_batch_id();
 
-    // Set up cancellation flag
-    let cancel = Arc::new(AtomicBool::new(false));
-    {
-        let mut flags = CANCEL_FLAGS.lock().unwrap();
-        flags.insert(batch_id.clone(), cancel.clone());
-    }
-
-    // Read credentials from store
-    let auth = {
-        let store_result = app_handle.store("credentials.json");
-        match store_result {
-            Ok(store) => {
-                let client_id = store
-                    .get("clientId")
-                    .and_then(|v| v.as_str().map(|s| s.to_string()))
-                    .unwrap_or_default();
-                let client_secret = store
-                    .get("clientSecret")
-                    .and_then(|v| v.as_str().map(|s| s.to_string()))
-                    .unwrap_or_default();
-                AuthHeaders {
-                    client_id,
-                    client_secret,
-                }
-            }
-            Err(_) => AuthHeaders {
-                client_id: String::new(),
-                client_secret: String::new(),
-            },
-        }
-    };
+    // Set up cancellation flag, cleared when the import task ends
+    let cancel = CancelRegistration::register(&batch_id);
+
+    let api = http.client(&config, &read_auth(&app_handle))?;
 
     // Initialize batch state
     {
@@ -133,6 +214,7 @@ pub async fn start_import(
 
     // Clone Arc for the spawned task (deref State -> Arc, then clone Arc)
     let state_arc: ManagedState = (*state).clone();
+    let queue_arc: ManagedQueue = (*queue).clone();
     let batch_id_ret = batch_id.clone();
 
     tokio::spawn(async move {
@@ -141,10 +223,10 @@ pub async fn start_import(
             &mount_path,
             &device_id,
             &batch_id,
-            &config.server_url,
-            &auth,
-            &config.ffmpeg_path,
-            &cancel,
+            &config,
+            &api,
+            &cancel.flag,
+            &queue_arc,
             &state_arc,
             &app_handle,
         )
@@ -171,26 +253,379 @@ pub async fn start_import(
                 },
             );
         }
-
-        // Clean up cancel flag
-        let mut flags = CANCEL_FLAGS.lock().unwrap();
-        flags.remove(&batch_id);
+        drop(cancel);
     });
 
     Ok(batch_id_ret)
 }
 
+/// A recording copied into the inbox and checked, before conversion.
+struct CopiedFile {
+    /// Hash of the file as read from the card
+    sha256: String,
+    repaired_sha256: Option<String>,
+    /// Where the file sits on the card
+    source_path: String,
+    local_path: String,
+    name: String,
+    size_bytes: u64,
+    modified: f64,
+    recorded_at: Option<RecordingTimestamp>,
+    audio: Option<AudioProbe>,
+}
+
+/// One recording to preflight and upload: a single file, one part of a split
+/// sequence, or the parts of a sequence joined together.
+struct ImportUnit {
+    file: CopiedFile,
+    sequence: Option<SequenceInfo>,
+    stitched_from: Option<Vec<String>>,
+}
+
+impl ImportUnit {
+    fn single(file: CopiedFile) -> Self {
+        Self {
+            file,
+            sequence: None,
+            stitched_from: None,
+        }
+    }
+}
+
+/// Join the parts of a split sequence into `<first part>_joined.<ext>` in the
+/// inbox. The joined file takes the first part's name and start time.
+async fn stitch_sequence(
+    parts: &[CopiedFile],
+    inbox_dir: &Path,
+    ffmpeg_path: Option<&str>,
+    app_handle: &AppHandle,
+) -> Result<CopiedFile, AppError> {
+    let first = &parts[0];
+    let total_size: u64 = parts.iter().map(|p| p.size_bytes).sum();
+    if !check_storage_limit(total_size).await? {
+        return Err(AppError::Other("Not enough inbox space to join the parts".into()));
+    }
+
+    let first_path = Path::new(&first.local_path);
+    let joined_name = format!(
+        "{}_joined.{}",
+        first_path.file_stem().unwrap_or_default().to_string_lossy(),
+        first_path.extension().unwrap_or_default().to_string_lossy()
+    );
+    let joined_str = inbox_dir.join(joined_name).to_string_lossy().to_string();
+
+    let part_paths: Vec<String> = parts.iter().map(|p| p.local_path.clone()).collect();
+    stitcher::stitch_recordings(&part_paths, &joined_str, ffmpeg_path).await?;
+
+    let sha256 = hasher::hash_file(joined_str.clone(), app_handle.clone()).await?;
+    let size_bytes = tokio::fs::metadata(&joined_str).await?.len();
+    let audio = probe::probe_audio(joined_str.clone(), ffmpeg_path.map(str::to_string))
+        .await
+        .ok();
+
+    Ok(CopiedFile {
+        sha256,
+        repaired_sha256: None,
+        source_path: first.source_path.clone(),
+        local_path: joined_str,
+        name: first.name.clone(),
+        size_bytes,
+        modified: parts[parts.len() - 1].modified,
+        recorded_at: first.recorded_at,
+        audio,
+    })
+}
+
+/// A recording in the inbox that the server has accepted for upload
+struct PendingUpload<'a> {
+    /// Hash of the file as read from the card, which keys the batch state
+    sha256: &'a str,
+    local_path: &'a str,
+    file_name: &'a str,
+    size_bytes: u64,
+    upload_sha256: &'a str,
+    chunks: &'a [ChunkInfo],
+    recording_id: &'a str,
+    upload_id: &'a str,
+    /// Peaks to store alongside the recording once it is up
+    waveform: Option<&'a WaveformPeaks>,
+    /// Batch cancellation flag
+    cancel: &'a Arc<AtomicBool>,
+    retry: &'a RetryConfig,
+    multipart: &'a MultipartConfig,
+    priority: UploadPriority,
+    /// Counts the batch's files as they start, for `uploading` progress
+    progress: Option<&'a UploadCount>,
+}
+
+/// Files of a batch that have started uploading, out of those queued
+struct UploadCount {
+    batch_id: String,
+    started: AtomicU32,
+    total: u32,
+}
+
+impl UploadCount {
+    fn start(&self, file_name: &str, app_handle: &AppHandle) {
+        let current = self.started.fetch_add(1, Ordering::Relaxed) + 1;
+        let _ = app_handle.emit(
+            "import-progress",
+            ImportProgress {
+                batch_id: self.batch_id.clone(),
+                phase: "uploading".to_string(),
+                current,
+                total: self.total,
+                file_name: Some(file_name.to_string()),
+                message: None,
+            },
+        );
+    }
+}
+
+/// Queue a recording for upload, and once it has a slot send its chunks,
+/// then the recording itself, whose arrival starts processing. The outcome,
+/// including cancellation while queued, is recorded in the batch state
+/// before the upload result is returned. A file that finds the server
+/// unreachable is parked to wait for the network rather than failed.
+async fn upload_recording(
+    upload: &PendingUpload<'_>,
+    queue: &UploadQueue,
+    api: &ApiClient,
+    batch_id: &str,
+    managed_state: &ManagedStateInner,
+    app_handle: &AppHandle,
+) -> Result<(), AppError> {
+    let job = UploadJob {
+        batch_id,
+        sha256: upload.sha256,
+        file_name: upload.file_name,
+        size_bytes: upload.size_bytes,
+        priority: upload.priority,
+    };
+    let result = queue
+        .run(job, upload.cancel, |cancel| async move {
+            if let Some(count) = upload.progress {
+                count.start(upload.file_name, app_handle);
+            }
+            info!("Uploading {}...", upload.file_name);
+            send_recording(upload, &cancel, api, batch_id, managed_state, app_handle).await
+        })
+        .await;
+
+    match &result {
+        Ok(()) => info!("Uploaded {}", upload.file_name),
+        Err(e) => error!("Failed to upload {}: {}", upload.file_name, e),
+    }
+    if let (Ok(()), Some(peaks)) = (&result, upload.waveform) {
+        // The page falls back to no waveform, so this never fails the upload
+        if let Err(e) = api_client::upload_waveform(
+            api,
+            upload.recording_id.to_string(),
+            peaks,
+        )
+        .await
+        {
+            warn!("Could not upload waveform of {}: {}", upload.file_name, e);
+        }
+    }
+    {
+        let mut app_state = managed_state.inner.lock().unwrap();
+        if let Some(batch) = app_state.batches.get_mut(batch_id) {
+            if let Some(fs) = batch.files.get_mut(upload.sha256) {
+                fs.waiting_for_network = None;
+                match &result {
+                    Ok(()) => {
+                        fs.verified = true;
+                        fs.error = None;
+                    }
+                    Err(e) if network::is_offline(e) => {
+                        fs.error = None;
+                        fs.waiting_for_network = Some(HeldUpload {
+                            local_path: upload.local_path.to_string(),
+                            original_file_name: upload.file_name.to_string(),
+                            size_bytes: upload.size_bytes,
+                            // Preflighted already, so never asked for again
+                            record_size_bytes: None,
+                        });
+                    }
+                    Err(e) => fs.error = Some(e.to_string()),
+                }
+            }
+        }
+    }
+    persist_state(managed_state).await?;
+
+    result
+}
+
+/// Send a recording once it has a slot and verify it arrived whole; `cancel`
+/// is the file's flag in the queue
+async fn send_recording(
+    upload: &PendingUpload<'_>,
+    cancel: &Arc<AtomicBool>,
+    api: &ApiClient,
+    batch_id: &str,
+    managed_state: &ManagedStateInner,
+    app_handle: &AppHandle,
+) -> Result<(), AppError> {
+    // A file sent before its verification was cut off only needs verifying
+    let mut sent = {
+        let app_state = managed_state.inner.lock().unwrap();
+        app_state
+            .batches
+            .get(batch_id)
+            .and_then(|b| b.files.get(upload.sha256))
+            .filter(|fs| fs.uploaded)
+            .and_then(|fs| fs.stored_sha256.clone())
+    };
+
+    // Chunks go up first, since the raw upload is what triggers processing
+    if sent.is_none() && !upload.chunks.is_empty() {
+        uploader::upload_chunks(
+            upload.chunks,
+            api,
+            upload.recording_id,
+            app_handle,
+            upload.retry,
+            cancel,
+        )
+        .await?;
+    }
+
+    // Storage checks each PUT against its checksum, but only the server's
+    // record shows the whole file arrived. One that never did or differs is
+    // sent again after a fresh presign, which the server refuses once
+    // processing has picked the recording up.
+    let size_bytes = tokio::fs::metadata(upload.local_path).await?.len();
+    let report_retry = || uploader::report_retry(app_handle, upload.recording_id, upload.file_name);
+    retry::with_retry_when(
+        upload.retry,
+        "Verified upload",
+        |e| matches!(e, AppError::NotReceived(_) | AppError::Mismatch(_)),
+        report_retry(),
+        || {
+            let sent = sent.take();
+            async move {
+                let stored_sha256 = match sent {
+                    Some(stored_sha256) => stored_sha256,
+                    None => {
+                        let stored_sha256 =
+                            send_raw(upload, cancel, api, batch_id, managed_state, app_handle)
+                                .await?;
+                        record_sent(managed_state, batch_id, upload.sha256, Some(&stored_sha256));
+                        persist_state(managed_state).await?;
+                        stored_sha256
+                    }
+                };
+                let verified = uploader::verify_upload(
+                    api,
+                    upload.recording_id,
+                    size_bytes,
+                    &stored_sha256,
+                    app_handle,
+                    upload.file_name,
+                    upload.retry,
+                )
+                .await;
+                if let Err(AppError::NotReceived(_) | AppError::Mismatch(_)) = &verified {
+                    record_sent(managed_state, batch_id, upload.sha256, None);
+                }
+                verified
+            }
+        },
+    )
+    .await
+}
+
+/// Record that a file's transfer finished, with the checksum storage should
+/// report for it, or with `None` that storage did not end up holding it
+fn record_sent(
+    managed_state: &ManagedStateInner,
+    batch_id: &str,
+    sha256: &str,
+    stored_sha256: Option<&str>,
+) {
+    let mut app_state = managed_state.inner.lock().unwrap();
+    if let Some(fs) = app_state
+        .batches
+        .get_mut(batch_id)
+        .and_then(|b| b.files.get_mut(sha256))
+    {
+        fs.uploaded = stored_sha256.is_some();
+        fs.stored_sha256 = stored_sha256.map(str::to_string);
+    }
+}
+
+/// Upload the recording itself, in parts when it is large. Returns the
+/// checksum the server should record for it: the file's SHA-256, or the
+/// composite of the parts' checksums for a multipart upload.
+async fn send_raw(
+    upload: &PendingUpload<'_>,
+    cancel: &Arc<AtomicBool>,
+    api: &ApiClient,
+    batch_id: &str,
+    managed_state: &ManagedStateInner,
+    app_handle: &AppHandle,
+) -> Result<String, AppError> {
+    let report_retry = || uploader::report_retry(app_handle, upload.recording_id, upload.file_name);
+    if upload.size_bytes > upload.multipart.threshold_bytes() {
+        uploader::upload_multipart(
+            upload.local_path,
+            api,
+            upload.recording_id,
+            upload.upload_id,
+            batch_id,
+            upload.sha256,
+            managed_state,
+            app_handle,
+            upload.file_name,
+            upload.retry,
+            upload.multipart,
+            cancel,
+        )
+        .await
+    } else {
+        let presign_result = retry::with_retry(upload.retry, "Presign", report_retry(), || {
+            api_client::presign(
+                api,
+                upload.recording_id.to_string(),
+                upload.upload_id.to_string(),
+                None,
+                hasher::hex_to_base64(upload.upload_sha256),
+            )
+        })
+        .await?;
+
+        retry::with_retry(upload.retry, "Upload", report_retry(), || {
+            uploader::upload_single(
+                upload.local_path,
+                &presign_result,
+                api,
+                app_handle,
+                upload.file_name,
+                upload.recording_id,
+                cancel,
+            )
+        })
+        .await?;
+        Ok(upload.upload_sha256.to_string())
+    }
+}
+
 async fn run_import(
     mount_path: &str,
     device_id: &str,
     batch_id: &str,
-    server_url: &str,
-    auth: &AuthHeaders,
-    ffmpeg_path: &str,
-    cancel: &AtomicBool,
+    config: &AppConfig,
+    api: &ApiClient,
+    cancel: &Arc<AtomicBool>,
+    queue: &UploadQueue,
     managed_state: &ManagedStateInner,
     app_handle: &AppHandle,
 ) -> Result<(), AppError> {
+    let ffmpeg_path = config.ffmpeg_path.as_str();
+    let split_sequences = config.split_sequences;
+
     // 1. Scan files
     let _ = app_handle.emit(
         "import-progress",
@@ -213,29 +648,33 @@ async fn run_import(
     let total = recordings.len() as u32;
     info!("Found {} audio file(s) on {}", total, mount_path);
 
+    // Recorder clock settings, used to turn file timestamps into absolute times
+    let recorder = match volumes::identify_device(mount_path.to_string()).await {
+        Ok(identifier) => Some(identifier),
+        Err(AppError::NotFound(_)) => None,
+        Err(e) => {
+            warn!("Ignoring RECORDER_ID.json on {}: {}", mount_path, e);
+            None
+        }
+    };
+
     // Check ffmpeg availability
     let has_ffmpeg = converter::check_ffmpeg(Some(ffmpeg_path.to_string())).await?;
     if !has_ffmpeg {
         warn!(
-            "ffmpeg not found at '{}'. Audio conversion will be skipped.",
+            "ffmpeg not found at '{}'. WAV files will use the built-in encoder; other formats are uploaded as-is.",
             ffmpeg_path
         );
     }
 
+    let profile = config.conversion_profile_for(device_id);
+
     // 2. Create inbox directory
     let inbox_dir = get_inbox_path().join(batch_id).join(device_id);
     tokio::fs::create_dir_all(&inbox_dir).await?;
 
     // 3. Copy files to inbox with hash computation
-    struct LocalFileInfo {
-        sha256: String,
-        local_path: String,
-        original_file_name: String,
-        size_bytes: u64,
-        modified: f64,
-    }
-
-    let mut file_infos = Vec::new();
+    let mut copied = Vec::new();
 
     for (idx, rec) in recordings.iter().enumerate() {
         if cancel.load(Ordering::Relaxed) {
@@ -271,67 +710,571 @@ async fn run_import(
         let sha256 =
             hasher::copy_with_hash(rec.path.clone(), dest_str.clone(), app_handle.clone()).await?;
 
-        let mut final_path = dest_str.clone();
+        // Validate container headers, repairing WAV sizes in the inbox copy where safe
+        let _ = app_handle.emit(
+            "import-progress",
+            ImportProgress {
+                batch_id: batch_id.to_string(),
+                phase: "validating".to_string(),
+                current: idx as u32 + 1,
+                total,
+                file_name: Some(rec.name.clone()),
+                message: None,
+            },
+        );
+
+        let mut repaired_sha256 = None;
+        match validator::validate_recording(&dest_str).await? {
+            ValidationOutcome::Valid => {}
+            ValidationOutcome::Repaired(detail) => {
+                warn!("Repaired WAV header of {}: {}", rec.name, detail);
+                repaired_sha256 =
+                    Some(hasher::hash_file(dest_str.clone(), app_handle.clone()).await?);
+            }
+            ValidationOutcome::Corrupt(reason) => {
+                error!("{} is corrupt and will not be uploaded: {}", rec.name, reason);
+                {
+                    let mut app_state = managed_state.inner.lock().unwrap();
+                    if let Some(batch) = app_state.batches.get_mut(batch_id) {
+                        batch.files.insert(
+                            sha256,
+                            FileStatus {
+                                error: Some(format!("Corrupt recording: {}", reason)),
+                                ..Default::default()
+                            },
+                        );
+                    }
+                }
+                persist_state(managed_state).await?;
+                continue;
+            }
+        }
+
+        let recorded_at = {
+            let path = dest_path.clone();
+            let name = rec.name.clone();
+            let modified = rec.modified;
+            tokio::task::spawn_blocking(move || {
+                timestamps::extract_recording_timestamp(&path, &name, modified)
+            })
+            .await
+            .map_err(|e| AppError::Other(e.to_string()))?
+        };
+
+        // Durations are needed to recognise auto-split sequences
+        let audio = match probe::probe_audio(dest_str.clone(), Some(ffmpeg_path.to_string())).await {
+            Ok(audio) => Some(audio),
+            Err(e) => {
+                warn!("Could not probe {}: {}", rec.name, e);
+                None
+            }
+        };
 
-        // Convert if needed
-        if has_ffmpeg && converter::needs_conversion(rec.name.clone(), rec.size) {
-            let converted_name = Path::new(&rec.name)
-                .file_stem()
-                .unwrap_or_default()
-                .to_string_lossy()
-                .to_string()
-                + ".mp3";
-            let converted_path = inbox_dir.join(&converted_name);
-            let converted_str = converted_path.to_string_lossy().to_string();
+        copied.push(CopiedFile {
+            sha256,
+            repaired_sha256,
+            source_path: rec.path.clone(),
+            local_path: dest_str,
+            name: rec.name.clone(),
+            size_bytes: rec.size,
+            modified: rec.modified,
+            recorded_at,
+            audio,
+        });
+    }
 
+    // 4. Group recorder auto-split sequences, joining them if configured
+    let sequences = if split_sequences == SequenceMode::Off {
+        Vec::new()
+    } else {
+        let candidates: Vec<_> = copied
+            .iter()
+            .map(|f| SequenceCandidate {
+                path: f.source_path.clone(),
+                name: f.name.clone(),
+                start_ms: f
+                    .recorded_at
+                    .as_ref()
+                    .filter(|t| t.source != TimestampSource::FileModified)
+                    .and_then(|t| t.resolve(recorder.as_ref()))
+                    .map(|dt| dt.timestamp_millis()),
+                modified_ms: f.modified as i64,
+                duration_ms: f.audio.as_ref().and_then(|a| a.duration_ms),
+            })
+            .collect();
+        scanner::detect_sequences(&candidates)
+    };
+
+    let sequence_of: HashMap<usize, usize> = sequences
+        .iter()
+        .enumerate()
+        .flat_map(|(seq, members)| members.iter().map(move |&m| (m, seq)))
+        .collect();
+
+    let mut pending: Vec<Option<CopiedFile>> = copied.into_iter().map(Some).collect();
+    let mut units: Vec<ImportUnit> = Vec::new();
+
+    for idx in 0..pending.len() {
+        let Some(&seq) = sequence_of.get(&idx) else {
+            if let Some(file) = pending[idx].take() {
+                units.push(ImportUnit::single(file));
+            }
+            continue;
+        };
+        let parts: Vec<CopiedFile> = sequences[seq]
+            .iter()
+            .filter_map(|&m| pending[m].take())
+            .collect();
+        if parts.is_empty() {
+            continue;
+        }
+
+        info!(
+            "Detected split recording: {}",
+            parts.iter().map(|p| p.name.as_str()).collect::<Vec<_>>().join(" + ")
+        );
+
+        if split_sequences == SequenceMode::Stitch {
+            let _ = app_handle.emit(
+                "import-progress",
+                ImportProgress {
+                    batch_id: batch_id.to_string(),
+                    phase: "stitching".to_string(),
+                    current: seq as u32 + 1,
+                    total: sequences.len() as u32,
+                    file_name: Some(parts[0].name.clone()),
+                    message: Some(format!("{} parts", parts.len())),
+                },
+            );
+
+            match stitch_sequence(&parts, &inbox_dir, has_ffmpeg.then_some(ffmpeg_path), app_handle)
+                .await
+            {
+                Ok(joined) => {
+                    for part in &parts {
+                        let _ = tokio::fs::remove_file(&part.local_path).await;
+                    }
+                    units.push(ImportUnit {
+                        stitched_from: Some(parts.into_iter().map(|p| p.name).collect()),
+                        ..ImportUnit::single(joined)
+                    });
+                    continue;
+                }
+                Err(e) => warn!(
+                    "Could not join {}, uploading its parts separately: {}",
+                    parts[0].name, e
+                ),
+            }
+        }
+
+        let sequence_id = format!("seq-{}", &parts[0].sha256[..16]);
+        let count = parts.len() as u32;
+        units.extend(parts.into_iter().enumerate().map(|(i, file)| ImportUnit {
+            sequence: Some(SequenceInfo {
+                sequence_id: sequence_id.clone(),
+                sequence_index: i as u32 + 1,
+                sequence_count: count,
+            }),
+            ..ImportUnit::single(file)
+        }));
+    }
+
+    // 5. Convert where needed and record each file in the batch
+    struct LocalFileInfo {
+        sha256: String,
+        local_path: String,
+        original_file_name: String,
+        /// Size recorded with the server
+        size_bytes: u64,
+        /// Length of the file actually uploaded, after repair and conversion
+        upload_size_bytes: u64,
+        recorded_at: Option<String>,
+        recorded_at_source: Option<TimestampSource>,
+        audio: Option<AudioProbe>,
+        sequence: Option<SequenceInfo>,
+        channel: Option<ChannelInfo>,
+        chunks: Vec<ChunkInfo>,
+        conversion_profile: Option<String>,
+        loudness: Option<LoudnessStats>,
+        upload_sha256: String,
+        waveform: Option<WaveformPeaks>,
+    }
+
+    let mut file_infos = Vec::new();
+    let unit_total = units.len() as u32;
+
+    for (idx, unit) in units.into_iter().enumerate() {
+        if cancel.load(Ordering::Relaxed) {
+            return Err(AppError::Cancelled);
+        }
+
+        let ImportUnit {
+            file,
+            sequence,
+            stitched_from,
+        } = unit;
+        let mut audio = file.audio.clone();
+
+        // Screen the untouched copy for speech; pre-processing would change its levels
+        let mut voice_activity = None;
+        if config.screening.enabled && !audio.as_ref().is_some_and(|a| a.is_empty()) {
             let _ = app_handle.emit(
                 "import-progress",
                 ImportProgress {
                     batch_id: batch_id.to_string(),
-                    phase: "converting".to_string(),
+                    phase: "screening".to_string(),
                     current: idx as u32 + 1,
-                    total,
-                    file_name: Some(rec.name.clone()),
+                    total: unit_total,
+                    file_name: Some(file.name.clone()),
                     message: None,
                 },
             );
 
-            info!("Converting {} -> MP3...", rec.name);
-            converter::convert_audio(
-                dest_path.to_string_lossy().to_string(),
-                converted_str.clone(),
-                Some(ffmpeg_path.to_string()),
+            match vad::analyze_voice_activity(
+                &file.local_path,
+                &config.screening,
+                has_ffmpeg.then_some(ffmpeg_path),
             )
-            .await?;
-            final_path = converted_str;
+            .await
+            {
+                Ok(activity) => {
+                    if activity.likely_empty {
+                        warn!(
+                            "{} is likely empty: {:.1}% speech, longest stretch {} ms",
+                            file.name,
+                            activity.speech_ratio * 100.0,
+                            activity.longest_speech_ms
+                        );
+                    }
+                    voice_activity = Some(activity);
+                }
+                Err(e) => warn!("Could not screen {} for speech: {}", file.name, e),
+            }
         }
+        let hold = config.screening.hold_empty
+            && voice_activity.as_ref().is_some_and(|v| v.likely_empty);
+
+        // Convert if needed: ffmpeg when available, otherwise the built-in encoder for WAV
+        let mut conversion_profile = None;
+        // Files conversion produced, each with its loudness measurement; empty
+        // when the original is uploaded as-is
+        let mut converted: Vec<(String, Option<LoudnessStats>)> = Vec::new();
+        let split = converter::splits_channels(&profile, audio.as_ref());
+        if converter::profile_applies(&profile, &file.name, file.size_bytes) {
+            let is_wav = Path::new(&file.name)
+                .extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|e| e.eq_ignore_ascii_case("wav"));
+
+            if has_ffmpeg || is_wav {
+                let stem = Path::new(&file.local_path)
+                    .file_stem()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .to_string();
+                let ext = if has_ffmpeg {
+                    profile.container.as_str()
+                } else {
+                    config.native_encoding.extension()
+                };
+                let output_paths: Vec<String> = if split {
+                    let count = audio.as_ref().and_then(|a| a.channels).unwrap_or(1);
+                    (1..=count)
+                        .map(|c| inbox_dir.join(format!("{}_ch{}.{}", stem, c, ext)))
+                        .map(|p| p.to_string_lossy().to_string())
+                        .collect()
+                } else {
+                    let mut converted_path = inbox_dir.join(format!("{}.{}", stem, ext));
+                    // Never overwrite the original (e.g. MP3 re-encoded to MP3); it stays for archival
+                    if converted_path == Path::new(&file.local_path) {
+                        converted_path = inbox_dir.join(format!("{}_processed.{}", stem, ext));
+                    }
+                    vec![converted_path.to_string_lossy().to_string()]
+                };
+
+                let _ = app_handle.emit(
+                    "import-progress",
+                    ImportProgress {
+                        batch_id: batch_id.to_string(),
+                        phase: "converting".to_string(),
+                        current: idx as u32 + 1,
+                        total: unit_total,
+                        file_name: Some(file.name.clone()),
+                        message: None,
+                    },
+                );
 
-        file_infos.push(LocalFileInfo {
-            sha256: sha256.clone(),
-            local_path: final_path,
-            original_file_name: rec.name.clone(),
-            size_bytes: rec.size,
-            modified: rec.modified,
-        });
+                if has_ffmpeg {
+                    let on_progress = |progress: &converter::FfmpegProgress| {
+                        let _ = app_handle.emit(
+                            "conversion-progress",
+                            ConversionProgress {
+                                batch_id: batch_id.to_string(),
+                                file_name: file.name.clone(),
+                                out_time_ms: progress.out_time_ms,
+                                percent: progress.percent,
+                                speed: progress.speed,
+                                eta_seconds: progress.eta_seconds,
+                            },
+                        );
+                    };
+                    let loudness = if split {
+                        info!(
+                            "Splitting {} into {} channels with profile '{}'...",
+                            file.name,
+                            output_paths.len(),
+                            profile.name
+                        );
+                        converter::split_with_profile(
+                            &file.local_path,
+                            &output_paths,
+                            ffmpeg_path,
+                            &profile,
+                            audio.as_ref(),
+                            cancel,
+                            on_progress,
+                        )
+                        .await?
+                    } else {
+                        info!("Converting {} with profile '{}'...", file.name, profile.name);
+                        vec![
+                            converter::convert_with_profile(
+                                &file.local_path,
+                                &output_paths[0],
+                                ffmpeg_path,
+                                &profile,
+                                audio.as_ref(),
+                                cancel,
+                                on_progress,
+                            )
+                            .await?,
+                        ]
+                    };
+                    converted = output_paths.into_iter().zip(loudness).collect();
+                    conversion_profile = Some(profile.name.clone());
+                } else {
+                    info!(
+                        "Converting {} -> {} (built-in encoder)...",
+                        file.name,
+                        config.native_encoding.extension()
+                    );
+                    let mut result = Ok(());
+                    for (c, output) in output_paths.iter().enumerate() {
+                        let channel = split.then_some(c);
+                        result = encoder::encode_wav(
+                            &file.local_path,
+                            output,
+                            config.native_encoding,
+                            channel,
+                        )
+                        .await;
+                        if result.is_err() {
+                            for done in &output_paths[..c] {
+                                let _ = tokio::fs::remove_file(done).await;
+                            }
+                            break;
+                        }
+                    }
+                    match result {
+                        Ok(()) => {
+                            converted = output_paths.into_iter().map(|p| (p, None)).collect();
+                            conversion_profile = Some(config.native_encoding.profile_name().to_string());
+                        }
+                        // e.g. ADPCM or 32-bit WAV in lossless mode: keep the original
+                        Err(AppError::InvalidInput(msg)) => {
+                            warn!("Uploading {} unconverted: {}", file.name, msg)
+                        }
+                        Err(e) => return Err(e),
+                    }
+                }
+            } else {
+                warn!("{} needs ffmpeg for conversion; uploading as-is", file.name);
+            }
+        }
 
-        // Update state
-        {
-            let mut app_state = managed_state.inner.lock().unwrap();
-            if let Some(batch) = app_state.batches.get_mut(batch_id) {
-                batch.files.insert(
-                    sha256,
-                    FileStatus {
-                        recording_id: String::new(),
-                        uploaded: false,
-                        ..Default::default()
+        // Each produced file becomes a recording; split channels are linked by group
+        let channel_count = converted.len() as u32;
+        let outputs: Vec<(String, Option<LoudnessStats>, Option<ChannelInfo>)> = if converted.is_empty() {
+            vec![(file.local_path.clone(), None, None)]
+        } else {
+            converted
+                .into_iter()
+                .enumerate()
+                .map(|(i, (path, loudness))| {
+                    let channel = (split && channel_count > 1).then(|| ChannelInfo {
+                        channel_group_id: format!("chn-{}", &file.sha256[..16]),
+                        channel_index: i as u32 + 1,
+                        channel_count,
+                    });
+                    (path, loudness, channel)
+                })
+                .collect()
+        };
+
+        for (final_path, loudness, channel) in outputs {
+            let file_name = match &channel {
+                Some(ch) => {
+                    let name = Path::new(&file.name);
+                    format!(
+                        "{}_ch{}.{}",
+                        name.file_stem().unwrap_or_default().to_string_lossy(),
+                        ch.channel_index,
+                        name.extension().unwrap_or_default().to_string_lossy()
+                    )
+                }
+                None => file.name.clone(),
+            };
+
+            if final_path != file.local_path {
+                // Probe the file that will actually be uploaded
+                audio = match probe::probe_audio(final_path.clone(), Some(ffmpeg_path.to_string())).await {
+                    Ok(audio) => Some(audio),
+                    Err(e) => {
+                        warn!("Could not probe {}: {}", file_name, e);
+                        None
+                    }
+                };
+            }
+
+            if audio.as_ref().is_some_and(|a| a.is_empty()) {
+                warn!("{} contains no audio (zero duration)", file_name);
+                let _ = app_handle.emit(
+                    "import-progress",
+                    ImportProgress {
+                        batch_id: batch_id.to_string(),
+                        phase: "probing".to_string(),
+                        current: idx as u32 + 1,
+                        total: unit_total,
+                        file_name: Some(file_name.clone()),
+                        message: Some("Zero-length recording".to_string()),
                     },
                 );
             }
+
+            // Split long recordings at silences for transcription
+            let mut chunks = Vec::new();
+            let duration_ms = audio.as_ref().and_then(|a| a.duration_ms).unwrap_or(0);
+            if config.chunking.enabled && duration_ms > config.chunking.max_chunk_minutes * 60_000 {
+                let _ = app_handle.emit(
+                    "import-progress",
+                    ImportProgress {
+                        batch_id: batch_id.to_string(),
+                        phase: "splitting".to_string(),
+                        current: idx as u32 + 1,
+                        total: unit_total,
+                        file_name: Some(file_name.clone()),
+                        message: None,
+                    },
+                );
+
+                info!("Splitting {} at silences...", file_name);
+                match splitter::split_audio(
+                    &final_path,
+                    duration_ms,
+                    &config.chunking,
+                    has_ffmpeg.then_some(ffmpeg_path),
+                )
+                .await
+                {
+                    Ok(planned) if planned.len() > 1 => chunks = planned,
+                    Ok(_) => {}
+                    Err(e) => warn!("Could not split {}, uploading it whole: {}", file_name, e),
+                }
+            }
+
+            // Cache peaks next to the inbox copy so the file list can draw it
+            let mut waveform = None;
+            if config.generate_waveforms && !audio.as_ref().is_some_and(|a| a.is_empty()) {
+                match waveform::generate_waveform(&final_path, has_ffmpeg.then_some(ffmpeg_path)).await {
+                    Ok(peaks) => waveform = config.upload_waveforms.then_some(peaks),
+                    Err(e) => warn!("Could not draw waveform of {}: {}", file_name, e),
+                }
+            }
+
+            // Digest of the bytes that will actually be sent, checked by storage on upload
+            let upload_sha256 = if final_path == file.local_path {
+                file.repaired_sha256.clone().unwrap_or_else(|| file.sha256.clone())
+            } else {
+                hasher::hash_file(final_path.clone(), app_handle.clone()).await?
+            };
+
+            let upload_size_bytes = tokio::fs::metadata(&final_path).await?.len();
+
+            // A channel is a recording of its own, identified by its own content
+            let (sha256, size_bytes) = match &channel {
+                Some(_) => (upload_sha256.clone(), upload_size_bytes),
+                None => (file.sha256.clone(), file.size_bytes),
+            };
+
+            let info = LocalFileInfo {
+                sha256: sha256.clone(),
+                local_path: final_path,
+                original_file_name: file_name,
+                size_bytes,
+                upload_size_bytes,
+                recorded_at: file
+                    .recorded_at
+                    .as_ref()
+                    .and_then(|t| t.resolve(recorder.as_ref()))
+                    .map(|dt| dt.to_rfc3339()),
+                recorded_at_source: file.recorded_at.as_ref().map(|t| t.source),
+                audio: audio.clone(),
+                sequence: sequence.clone(),
+                channel: channel.clone(),
+                chunks,
+                conversion_profile: conversion_profile.clone(),
+                loudness,
+                upload_sha256,
+                waveform,
+            };
+
+            // Update state
+            {
+                let mut app_state = managed_state.inner.lock().unwrap();
+                if let Some(batch) = app_state.batches.get_mut(batch_id) {
+                    batch.files.insert(
+                        sha256,
+                        FileStatus {
+                            recording_id: String::new(),
+                            uploaded: false,
+                            recorded_at: info.recorded_at.clone(),
+                            recorded_at_source: info.recorded_at_source,
+                            audio: info.audio.clone(),
+                            original_sha256: Some(file.sha256.clone()),
+                            repaired_sha256: file.repaired_sha256.clone(),
+                            sequence: sequence.clone(),
+                            stitched_from: stitched_from.clone(),
+                            channel,
+                            chunks: (!info.chunks.is_empty()).then(|| info.chunks.clone()),
+                            conversion_profile: info.conversion_profile.clone(),
+                            loudness: info.loudness.clone(),
+                            upload_sha256: Some(info.upload_sha256.clone()),
+                            local_path: Some(info.local_path.clone()),
+                            voice_activity: voice_activity.clone(),
+                            held: hold.then(|| HeldUpload {
+                                local_path: info.local_path.clone(),
+                                original_file_name: info.original_file_name.clone(),
+                                size_bytes: info.upload_size_bytes,
+                                record_size_bytes: Some(info.size_bytes),
+                            }),
+                            ..Default::default()
+                        },
+                    );
+                }
+            }
+            persist_state(managed_state).await?;
+
+            if hold {
+                info!("Holding {} until its upload is confirmed", info.original_file_name);
+            } else {
+                file_infos.push(info);
+            }
         }
-        persist_state(managed_state).await?;
     }
 
     if file_infos.is_empty() {
+        finalize_batch_status(managed_state, batch_id);
+        persist_state(managed_state).await?;
         return Ok(());
     }
 
@@ -339,7 +1282,7 @@ async fn run_import(
         return Err(AppError::Cancelled);
     }
 
-    // 4. Preflight batch
+    // 6. Preflight batch
     let _ = app_handle.emit(
         "import-progress",
         ImportProgress {
@@ -359,25 +1302,63 @@ async fn run_import(
         .map(|f| PreflightFile {
             device_id: device_id.to_string(),
             original_file_name: f.original_file_name.clone(),
-            recorder_file_created_at: Some(
-                chrono::DateTime::from_timestamp_millis(f.modified as i64)
-                    .map(|dt| dt.to_rfc3339())
-                    .unwrap_or_default(),
-            ),
+            recorder_file_created_at: f.recorded_at.clone(),
+            recorder_file_created_at_source: f.recorded_at_source,
             size_bytes: f.size_bytes,
             sha256: f.sha256.clone(),
+            audio: f.audio.clone(),
+            sequence: f.sequence.clone(),
+            channel: f.channel.clone(),
         })
         .collect();
 
-    let preflight_results = api_client::preflight_batch(
-        server_url.to_string(),
-        auth.clone(),
+    let preflight_results = match api_client::preflight_batch(
+        api,
         batch_id.to_string(),
         preflight_files,
     )
-    .await?;
+    .await
+    {
+        Ok(results) => results,
+        Err(e) if network::is_offline(&e) => {
+            // Everything local is done; park the files until the server answers
+            warn!("Server unreachable, batch {} waits for the network: {}", batch_id, e);
+            {
+                let mut app_state = managed_state.inner.lock().unwrap();
+                if let Some(batch) = app_state.batches.get_mut(batch_id) {
+                    for info in &file_infos {
+                        if let Some(fs) = batch.files.get_mut(&info.sha256) {
+                            fs.waiting_for_network = Some(HeldUpload {
+                                local_path: info.local_path.clone(),
+                                original_file_name: info.original_file_name.clone(),
+                                size_bytes: info.upload_size_bytes,
+                                record_size_bytes: Some(info.size_bytes),
+                            });
+                        }
+                    }
+                }
+            }
+            finalize_batch_status(managed_state, batch_id);
+            persist_state(managed_state).await?;
+            network::emit_backlog(app_handle, managed_state);
 
-    // 5. Update state with server response
+            let _ = app_handle.emit(
+                "import-progress",
+                ImportProgress {
+                    batch_id: batch_id.to_string(),
+                    phase: "waiting_for_network".to_string(),
+                    current: 0,
+                    total: file_infos.len() as u32,
+                    file_name: None,
+                    message: Some(e.to_string()),
+                },
+            );
+            return Ok(());
+        }
+        Err(e) => return Err(e),
+    };
+
+    // 7. Update state with server response
     let new_files: Vec<_> = preflight_results
         .iter()
         .filter(|r| r.status == "NEW")
@@ -410,7 +1391,7 @@ async fn run_import(
         return Err(AppError::Cancelled);
     }
 
-    // 6. Upload new files
+    // 8. Upload new files
     if !new_files.is_empty() {
         info!("Uploading {} new file(s)...", new_files.len());
 
@@ -422,148 +1403,55 @@ async fn run_import(
         }
         persist_state(managed_state).await?;
 
-        for (idx, result) in new_files.iter().enumerate() {
-            if cancel.load(Ordering::Relaxed) {
-                return Err(AppError::Cancelled);
-            }
-
-            let file_info = file_infos.iter().find(|f| f.sha256 == result.sha256);
-            let upload_id = match (&file_info, &result.upload_id) {
-                (Some(_), Some(uid)) => uid.clone(),
-                _ => continue,
-            };
-            let file_info = file_info.unwrap();
-
-            let _ = app_handle.emit(
-                "import-progress",
-                ImportProgress {
-                    batch_id: batch_id.to_string(),
-                    phase: "uploading".to_string(),
-                    current: idx as u32 + 1,
-                    total: new_files.len() as u32,
-                    file_name: Some(file_info.original_file_name.clone()),
-                    message: None,
-                },
-            );
-
-            info!("Uploading {}...", file_info.original_file_name);
-
-            let upload_result = if file_info.size_bytes > uploader::MULTIPART_THRESHOLD {
-                // Multipart upload
-                let presign_result = api_client::presign(
-                    server_url.to_string(),
-                    auth.clone(),
-                    result.recording_id.clone(),
-                    upload_id.clone(),
-                    Some(true),
-                )
-                .await?;
-
-                let mp_upload_id = presign_result
-                    .upload_id
-                    .clone()
-                    .unwrap_or_else(|| upload_id.clone());
-
-                uploader::upload_multipart(
-                    &file_info.local_path,
-                    server_url,
-                    auth,
-                    &result.recording_id,
-                    &mp_upload_id,
-                    batch_id,
-                    &result.sha256,
-                    managed_state,
-                    app_handle,
-                    &file_info.original_file_name,
-                )
-                .await
-            } else {
-                // Single upload
-                let presign_result = api_client::presign(
-                    server_url.to_string(),
-                    auth.clone(),
-                    result.recording_id.clone(),
-                    upload_id.clone(),
-                    None,
-                )
-                .await?;
-
-                uploader::upload_single(
-                    &file_info.local_path,
-                    &presign_result,
-                    app_handle,
-                    &file_info.original_file_name,
-                    &result.recording_id,
-                )
-                .await
-            };
-
-            match upload_result {
-                Ok(()) => {
-                    info!("Uploaded {}", file_info.original_file_name);
-                    {
-                        let mut app_state = managed_state.inner.lock().unwrap();
-                        if let Some(batch) = app_state.batches.get_mut(batch_id) {
-                            if let Some(fs) = batch.files.get_mut(&result.sha256) {
-                                fs.uploaded = true;
-                            }
-                        }
-                    }
-                    persist_state(managed_state).await?;
-                }
-                Err(e) => {
-                    error!(
-                        "Failed to upload {}: {}",
-                        file_info.original_file_name, e
-                    );
-                    {
-                        let mut app_state = managed_state.inner.lock().unwrap();
-                        if let Some(batch) = app_state.batches.get_mut(batch_id) {
-                            if let Some(fs) = batch.files.get_mut(&result.sha256) {
-                                fs.error = Some(e.to_string());
-                            }
-                        }
-                    }
-                    persist_state(managed_state).await?;
-                }
-            }
-        }
-
-        // Update batch status
-        let final_status = {
-            let app_state = managed_state.inner.lock().unwrap();
-            if let Some(batch) = app_state.batches.get(batch_id) {
-                let has_errors = batch.files.values().any(|f| f.error.is_some());
-                let all_uploaded = batch.files.values().all(|f| f.uploaded);
-                if all_uploaded {
-                    BatchStatus::Completed
-                } else if has_errors {
-                    BatchStatus::PartialError
-                } else {
-                    BatchStatus::Uploading
-                }
-            } else {
-                BatchStatus::Completed
-            }
+        let count = UploadCount {
+            batch_id: batch_id.to_string(),
+            started: AtomicU32::new(0),
+            total: new_files.len() as u32,
         };
-
-        {
-            let mut app_state = managed_state.inner.lock().unwrap();
-            if let Some(batch) = app_state.batches.get_mut(batch_id) {
-                batch.status = final_status.clone();
-            }
+        let uploads: Vec<PendingUpload> = new_files
+            .iter()
+            .filter_map(|result| {
+                let file_info = file_infos.iter().find(|f| f.sha256 == result.sha256)?;
+                Some(PendingUpload {
+                    sha256: &result.sha256,
+                    local_path: &file_info.local_path,
+                    file_name: &file_info.original_file_name,
+                    size_bytes: file_info.upload_size_bytes,
+                    upload_sha256: &file_info.upload_sha256,
+                    chunks: &file_info.chunks,
+                    recording_id: &result.recording_id,
+                    upload_id: result.upload_id.as_deref()?,
+                    waveform: file_info.waveform.as_ref(),
+                    cancel,
+                    retry: &config.retry,
+                    multipart: &config.multipart,
+                    priority: UploadPriority::Normal,
+                    progress: Some(&count),
+                })
+            })
+            .collect();
+        // All files wait in the shared queue, which decides when each goes.
+        // Failures are recorded on the file; the rest of the batch carries on.
+        futures::future::join_all(uploads.iter().map(|upload| {
+            upload_recording(upload, queue, api, batch_id, managed_state, app_handle)
+        }))
+        .await;
+
+        // An upload cut off mid-stream is recorded on its file; stop here too
+        if cancel.load(Ordering::Relaxed) {
+            return Err(AppError::Cancelled);
         }
+
+        let final_status = finalize_batch_status(managed_state, batch_id);
         persist_state(managed_state).await?;
+        if final_status == Some(BatchStatus::WaitingForNetwork) {
+            network::emit_backlog(app_handle, managed_state);
+        }
 
         info!("Batch {} status: {:?}", batch_id, final_status);
     } else {
         info!("All files already uploaded.");
-        {
-            let mut app_state = managed_state.inner.lock().unwrap();
-            if let Some(batch) = app_state.batches.get_mut(batch_id) {
-                batch.status = BatchStatus::Completed;
-            }
-        }
+        finalize_batch_status(managed_state, batch_id);
         persist_state(managed_state).await?;
     }
 
@@ -596,33 +1484,14 @@ pub async fn upload_files(
     files: Vec<ManualUploadFile>,
     app_handle: AppHandle,
     state: State<'_, ManagedState>,
+    queue: State<'_, ManagedQueue>,
+    http: State<'_, ManagedHttp>,
 ) -> Result<String, AppError> {
     let config = read_config().await?;
     let batch_id = generate_batch_id();
+    let cancel = CancelRegistration::register(&batch_id);
 
-    let auth = {
-        let store_result = app_handle.store("credentials.json");
-        match store_result {
-            Ok(store) => {
-                let client_id = store
-                    .get("clientId")
-                    .and_then(|v| v.as_str().map(|s| s.to_string()))
-                    .unwrap_or_default();
-                let client_secret = store
-                    .get("clientSecret")
-                    .and_then(|v| v.as_str().map(|s| s.to_string()))
-                    .unwrap_or_default();
-                AuthHeaders {
-                    client_id,
-                    client_secret,
-                }
-            }
-            Err(_) => AuthHeaders {
-                client_id: String::new(),
-                client_secret: String::new(),
-            },
-        }
-    };
+    let api = http.client(&config, &read_auth(&app_handle))?;
 
     // Initialize batch state
     {
@@ -638,39 +1507,122 @@ pub async fn upload_files(
     }
     persist_state(&state).await?;
 
-    // Hash each file
+    // Hash each file and work out when it was recorded
+    struct ManualFileInfo<'a> {
+        file: &'a ManualUploadFile,
+        sha256: String,
+        recorded_at: Option<timestamps::RecordingTimestamp>,
+        audio: Option<AudioProbe>,
+    }
+
     let mut file_infos = Vec::new();
     for file in &files {
         info!("Hashing {}...", file.name);
         let sha256 = hasher::hash_file(file.path.clone(), app_handle.clone()).await?;
-        file_infos.push((file, sha256));
+        let modified = tokio::fs::metadata(&file.path)
+            .await?
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|d| d.as_secs_f64() * 1000.0)
+            .unwrap_or(0.0);
+        let recorded_at = {
+            let path = std::path::PathBuf::from(&file.path);
+            let name = file.name.clone();
+            tokio::task::spawn_blocking(move || {
+                timestamps::extract_recording_timestamp(&path, &name, modified)
+            })
+            .await
+            .map_err(|e| AppError::Other(e.to_string()))?
+        };
+        let audio = match probe::probe_audio(file.path.clone(), Some(config.ffmpeg_path.clone())).await {
+            Ok(audio) => Some(audio),
+            Err(e) => {
+                warn!("Could not probe {}: {}", file.name, e);
+                None
+            }
+        };
+        file_infos.push(ManualFileInfo {
+            file,
+            sha256,
+            recorded_at,
+            audio,
+        });
     }
 
     // Preflight
     let preflight_files: Vec<PreflightFile> = file_infos
         .iter()
-        .map(|(f, sha256)| PreflightFile {
+        .map(|f| PreflightFile {
             device_id: String::new(),
-            original_file_name: f.name.clone(),
-            recorder_file_created_at: None,
-            size_bytes: f.size_bytes,
-            sha256: sha256.clone(),
+            original_file_name: f.file.name.clone(),
+            recorder_file_created_at: f
+                .recorded_at
+                .as_ref()
+                .and_then(|t| t.resolve(None))
+                .map(|dt| dt.to_rfc3339()),
+            recorder_file_created_at_source: f.recorded_at.as_ref().map(|t| t.source),
+            size_bytes: f.file.size_bytes,
+            sha256: f.sha256.clone(),
+            audio: f.audio.clone(),
+            sequence: None,
+            channel: None,
         })
         .collect();
 
-    let preflight_results = api_client::preflight_batch(
-        config.server_url.clone(),
-        auth.clone(),
+    let preflight_results = match api_client::preflight_batch(
+        &api,
         batch_id.clone(),
         preflight_files,
     )
-    .await?;
+    .await
+    {
+        Ok(results) => results,
+        Err(e) if network::is_offline(&e) => {
+            // Park the selection until the server answers
+            warn!("Server unreachable, batch {} waits for the network: {}", batch_id, e);
+            {
+                let mut app_state = state.inner.lock().unwrap();
+                if let Some(batch) = app_state.batches.get_mut(&batch_id) {
+                    for f in &file_infos {
+                        batch.files.insert(
+                            f.sha256.clone(),
+                            FileStatus {
+                                recorded_at: f
+                                    .recorded_at
+                                    .as_ref()
+                                    .and_then(|t| t.resolve(None))
+                                    .map(|dt| dt.to_rfc3339()),
+                                recorded_at_source: f.recorded_at.as_ref().map(|t| t.source),
+                                audio: f.audio.clone(),
+                                original_sha256: Some(f.sha256.clone()),
+                                upload_sha256: Some(f.sha256.clone()),
+                                waiting_for_network: Some(HeldUpload {
+                                    local_path: f.file.path.clone(),
+                                    original_file_name: f.file.name.clone(),
+                                    size_bytes: f.file.size_bytes,
+                                    record_size_bytes: None,
+                                }),
+                                ..Default::default()
+                            },
+                        );
+                    }
+                }
+            }
+            finalize_batch_status(&state, &batch_id);
+            persist_state(&state).await?;
+            network::emit_backlog(&app_handle, &state);
+            return Ok(batch_id);
+        }
+        Err(e) => return Err(e),
+    };
 
     // Update state
     {
         let mut app_state = state.inner.lock().unwrap();
         if let Some(batch) = app_state.batches.get_mut(&batch_id) {
             for result in &preflight_results {
+                let entry = file_infos.iter().find(|f| f.sha256 == result.sha256);
                 batch.files.insert(
                     result.sha256.clone(),
                     FileStatus {
@@ -678,6 +1630,17 @@ pub async fn upload_files(
                         uploaded: result.status == "ALREADY_EXISTS",
                         upload_id: result.upload_id.clone(),
                         raw_r2_key: result.raw_r2_key.clone(),
+                        recorded_at: entry
+                            .and_then(|f| f.recorded_at.as_ref())
+                            .and_then(|t| t.resolve(None))
+                            .map(|dt| dt.to_rfc3339()),
+                        recorded_at_source: entry
+                            .and_then(|f| f.recorded_at.as_ref())
+                            .map(|t| t.source),
+                        audio: entry.and_then(|f| f.audio.clone()),
+                        // Manual uploads send the selected file unchanged
+                        original_sha256: Some(result.sha256.clone()),
+                        upload_sha256: Some(result.sha256.clone()),
                         ..Default::default()
                     },
                 );
@@ -687,99 +1650,356 @@ pub async fn upload_files(
     }
     persist_state(&state).await?;
 
-    // Upload NEW files
-    let new_files: Vec<_> = preflight_results
+    // Upload NEW files, ahead of queued imports
+    let uploads: Vec<PendingUpload> = preflight_results
         .iter()
         .filter(|r| r.status == "NEW")
+        .filter_map(|result| {
+            let file = file_infos.iter().find(|f| f.sha256 == result.sha256)?.file;
+            Some(PendingUpload {
+                sha256: &result.sha256,
+                local_path: &file.path,
+                file_name: &file.name,
+                size_bytes: file.size_bytes,
+                // Manual uploads send the selected file unchanged
+                upload_sha256: &result.sha256,
+                chunks: &[],
+                recording_id: &result.recording_id,
+                upload_id: result.upload_id.as_deref()?,
+                waveform: None,
+                cancel: &cancel.flag,
+                retry: &config.retry,
+                multipart: &config.multipart,
+                priority: UploadPriority::High,
+                progress: None,
+            })
+        })
         .collect();
+    futures::future::join_all(uploads.iter().map(|upload| {
+        upload_recording(upload, &queue, &api, &batch_id, &state, &app_handle)
+    }))
+    .await;
 
-    let state_arc: ManagedState = (*state).clone();
+    let final_status = finalize_batch_status(&state, &batch_id);
+    persist_state(&state).await?;
+    if final_status == Some(BatchStatus::WaitingForNetwork) {
+        network::emit_backlog(&app_handle, &state);
+    }
 
-    for result in &new_files {
-        let file_entry = file_infos.iter().find(|(_, sha)| sha == &result.sha256);
-        let upload_id = match (&file_entry, &result.upload_id) {
-            (Some(_), Some(uid)) => uid.clone(),
-            _ => continue,
-        };
-        let (file, _) = file_entry.unwrap();
-
-        if file.size_bytes > uploader::MULTIPART_THRESHOLD {
-            let presign_result = api_client::presign(
-                config.server_url.clone(),
-                auth.clone(),
-                result.recording_id.clone(),
-                upload_id.clone(),
-                Some(true),
-            )
-            .await?;
-
-            let mp_upload_id = presign_result
-                .upload_id
-                .clone()
-                .unwrap_or_else(|| upload_id.clone());
-
-            uploader::upload_multipart(
-                &file.path,
-                &config.server_url,
-                &auth,
-                &result.recording_id,
-                &mp_upload_id,
-                &batch_id,
-                &result.sha256,
-                &state_arc,
-                &app_handle,
-                &file.name,
-            )
-            .await?;
-        } else {
-            let presign_result = api_client::presign(
-                config.server_url.clone(),
-                auth.clone(),
-                result.recording_id.clone(),
-                upload_id,
-                None,
-            )
-            .await?;
+    Ok(batch_id)
+}
 
-            uploader::upload_single(
-                &file.path,
-                &presign_result,
-                &app_handle,
-                &file.name,
-                &result.recording_id,
-            )
-            .await?;
-        }
+/// Decide on a recording held back as likely empty: upload it after all, or
+/// leave it out of the batch. Discarded files stay in the inbox until the
+/// batch is cleaned.
+#[tauri::command]
+pub async fn resolve_held_file(
+    batch_id: String,
+    sha256: String,
+    upload: bool,
+    app_handle: AppHandle,
+    state: State<'_, ManagedState>,
+    queue: State<'_, ManagedQueue>,
+    http: State<'_, ManagedHttp>,
+) -> Result<(), AppError> {
+    let (held, file_status, device_id) = {
+        let app_state = state.inner.lock().unwrap();
+        let batch = app_state
+            .batches
+            .get(&batch_id)
+            .ok_or_else(|| AppError::NotFound(format!("Batch {} not found", batch_id)))?;
+        let file_status = batch
+            .files
+            .get(&sha256)
+            .ok_or_else(|| AppError::NotFound(format!("File {} not found in batch {}", sha256, batch_id)))?;
+        let held = file_status
+            .held
+            .clone()
+            .ok_or_else(|| AppError::InvalidInput(format!("File {} is not held for review", sha256)))?;
+        (held, file_status.clone(), batch.device_id.clone())
+    };
 
-        // Mark as uploaded
+    if !upload {
+        info!("Discarding held recording {}", held.original_file_name);
         {
-            let mut app_state = state_arc.inner.lock().unwrap();
-            if let Some(batch) = app_state.batches.get_mut(&batch_id) {
-                if let Some(fs) = batch.files.get_mut(&result.sha256) {
-                    fs.uploaded = true;
-                }
+            let mut app_state = state.inner.lock().unwrap();
+            if let Some(fs) = app_state
+                .batches
+                .get_mut(&batch_id)
+                .and_then(|b| b.files.get_mut(&sha256))
+            {
+                fs.held = None;
+                fs.discarded = true;
             }
         }
-        persist_state(&state_arc).await?;
+        finalize_batch_status(&state, &batch_id);
+        persist_state(&state).await?;
+        return Ok(());
     }
 
-    // Final status
+    let config = read_config().await?;
+    let api = http.client(&config, &read_auth(&app_handle))?;
+    let cancel = CancelRegistration::register(&batch_id);
+
+    info!("Upload of held recording {} confirmed", held.original_file_name);
+    let preflight_results = match api_client::preflight_batch(
+        &api,
+        batch_id.clone(),
+        vec![PreflightFile {
+            device_id,
+            original_file_name: held.original_file_name.clone(),
+            recorder_file_created_at: file_status.recorded_at.clone(),
+            recorder_file_created_at_source: file_status.recorded_at_source,
+            size_bytes: held.record_size(),
+            sha256: sha256.clone(),
+            audio: file_status.audio.clone(),
+            sequence: file_status.sequence.clone(),
+            channel: file_status.channel.clone(),
+        }],
+    )
+    .await
+    {
+        Ok(results) => results,
+        Err(e) if network::is_offline(&e) => {
+            // Confirmed, so it no longer needs review; it goes once the server answers
+            warn!(
+                "Server unreachable, {} waits for the network: {}",
+                held.original_file_name, e
+            );
+            {
+                let mut app_state = state.inner.lock().unwrap();
+                if let Some(fs) = app_state
+                    .batches
+                    .get_mut(&batch_id)
+                    .and_then(|b| b.files.get_mut(&sha256))
+                {
+                    fs.held = None;
+                    fs.waiting_for_network = Some(held);
+                }
+            }
+            finalize_batch_status(&state, &batch_id);
+            persist_state(&state).await?;
+            network::emit_backlog(&app_handle, &state);
+            return Ok(());
+        }
+        Err(e) => return Err(e),
+    };
+    let result = preflight_results
+        .into_iter()
+        .find(|r| r.sha256 == sha256)
+        .ok_or_else(|| AppError::Other("Preflight returned no result for the file".into()))?;
+
     {
-        let mut app_state = state_arc.inner.lock().unwrap();
+        let mut app_state = state.inner.lock().unwrap();
         if let Some(batch) = app_state.batches.get_mut(&batch_id) {
-            batch.status = BatchStatus::Completed;
+            batch.status = BatchStatus::Uploading;
+            if let Some(fs) = batch.files.get_mut(&sha256) {
+                fs.held = None;
+                fs.recording_id = result.recording_id.clone();
+                fs.upload_id = result.upload_id.clone();
+                fs.raw_r2_key = result.raw_r2_key.clone();
+                fs.uploaded = result.status == "ALREADY_EXISTS";
+            }
         }
     }
-    persist_state(&state_arc).await?;
+    persist_state(&state).await?;
 
-    Ok(batch_id)
+    let upload_result = match (result.status.as_str(), &result.upload_id) {
+        ("NEW", Some(upload_id)) => {
+            let upload_sha256 = file_status.upload_sha256.unwrap_or_else(|| sha256.clone());
+            let chunks = file_status.chunks.unwrap_or_default();
+            let waveform = match config.upload_waveforms {
+                true => waveform::load_cached_waveform(&held.local_path).await,
+                false => None,
+            };
+            let pending = PendingUpload {
+                sha256: &sha256,
+                local_path: &held.local_path,
+                file_name: &held.original_file_name,
+                size_bytes: held.size_bytes,
+                upload_sha256: &upload_sha256,
+                chunks: &chunks,
+                recording_id: &result.recording_id,
+                upload_id,
+                waveform: waveform.as_ref(),
+                cancel: &cancel.flag,
+                retry: &config.retry,
+                multipart: &config.multipart,
+                priority: UploadPriority::High,
+                progress: None,
+            };
+            upload_recording(&pending, &queue, &api, &batch_id, &state, &app_handle)
+                .await
+        }
+        _ => Ok(()),
+    };
+
+    let final_status = finalize_batch_status(&state, &batch_id);
+    persist_state(&state).await?;
+    if final_status == Some(BatchStatus::WaitingForNetwork) {
+        network::emit_backlog(&app_handle, &state);
+    }
+
+    upload_result
+}
+
+/// Preflight and upload the files of a batch that waited for the network.
+/// Files already preflighted keep their recording and go straight to upload.
+/// Any that find the server unreachable again go back to waiting.
+pub(crate) async fn resume_offline_batch(
+    batch_id: &str,
+    app_handle: &AppHandle,
+    managed_state: &ManagedStateInner,
+    queue: &UploadQueue,
+    http: &HttpClients,
+) -> Result<(), AppError> {
+    let cancel = CancelRegistration::register(batch_id);
+    let config = read_config().await?;
+    let api = http.client(&config, &read_auth(app_handle))?;
+
+    let (device_id, to_preflight) = {
+        let mut app_state = managed_state.inner.lock().unwrap();
+        let batch = app_state
+            .batches
+            .get_mut(batch_id)
+            .ok_or_else(|| AppError::NotFound(format!("Batch {} not found", batch_id)))?;
+        batch.status = BatchStatus::Uploading;
+        let to_preflight: Vec<PreflightFile> = batch
+            .files
+            .iter()
+            .filter_map(|(sha256, fs)| {
+                let file = fs.waiting_for_network.as_ref()?;
+                fs.upload_id.is_none().then(|| PreflightFile {
+                    device_id: batch.device_id.clone(),
+                    original_file_name: file.original_file_name.clone(),
+                    recorder_file_created_at: fs.recorded_at.clone(),
+                    recorder_file_created_at_source: fs.recorded_at_source,
+                    size_bytes: file.record_size(),
+                    sha256: sha256.clone(),
+                    audio: fs.audio.clone(),
+                    sequence: fs.sequence.clone(),
+                    channel: fs.channel.clone(),
+                })
+            })
+            .collect();
+        (batch.device_id.clone(), to_preflight)
+    };
+    info!("Resuming batch {} of device {}", batch_id, device_id);
+
+    if !to_preflight.is_empty() {
+        let sha256s: Vec<String> = to_preflight.iter().map(|f| f.sha256.clone()).collect();
+        let preflight = api_client::preflight_batch(
+            &api,
+            batch_id.to_string(),
+            to_preflight,
+        )
+        .await;
+        {
+            let mut app_state = managed_state.inner.lock().unwrap();
+            if let Some(batch) = app_state.batches.get_mut(batch_id) {
+                match &preflight {
+                    Ok(results) => {
+                        for result in results {
+                            if let Some(fs) = batch.files.get_mut(&result.sha256) {
+                                fs.recording_id = result.recording_id.clone();
+                                fs.upload_id = result.upload_id.clone();
+                                fs.raw_r2_key = result.raw_r2_key.clone();
+                                if result.status == "ALREADY_EXISTS" {
+                                    fs.uploaded = true;
+                                    fs.waiting_for_network = None;
+                                }
+                            }
+                        }
+                    }
+                    // Still unreachable; the files keep waiting
+                    Err(e) if network::is_offline(e) => {}
+                    Err(e) => {
+                        for sha256 in &sha256s {
+                            if let Some(fs) = batch.files.get_mut(sha256) {
+                                fs.waiting_for_network = None;
+                                fs.error = Some(e.to_string());
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        if let Err(e) = &preflight {
+            warn!("Preflight of waiting batch {} failed: {}", batch_id, e);
+        }
+    }
+
+    let waiting: Vec<(String, FileStatus, HeldUpload)> = {
+        let app_state = managed_state.inner.lock().unwrap();
+        app_state
+            .batches
+            .get(batch_id)
+            .map(|batch| {
+                batch
+                    .files
+                    .iter()
+                    .filter_map(|(sha256, fs)| {
+                        let file = fs.waiting_for_network.clone()?;
+                        fs.upload_id.is_some().then(|| (sha256.clone(), fs.clone(), file))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    let mut waveforms = Vec::with_capacity(waiting.len());
+    for (_, _, file) in &waiting {
+        waveforms.push(match config.upload_waveforms {
+            true => waveform::load_cached_waveform(&file.local_path).await,
+            false => None,
+        });
+    }
+
+    let count = UploadCount {
+        batch_id: batch_id.to_string(),
+        started: AtomicU32::new(0),
+        total: waiting.len() as u32,
+    };
+    let uploads: Vec<PendingUpload> = waiting
+        .iter()
+        .zip(&waveforms)
+        .filter_map(|((sha256, fs, file), waveform)| {
+            Some(PendingUpload {
+                sha256,
+                local_path: &file.local_path,
+                file_name: &file.original_file_name,
+                size_bytes: file.size_bytes,
+                upload_sha256: fs.upload_sha256.as_deref().unwrap_or(sha256),
+                chunks: fs.chunks.as_deref().unwrap_or_default(),
+                recording_id: &fs.recording_id,
+                upload_id: fs.upload_id.as_deref()?,
+                waveform: waveform.as_ref(),
+                cancel: &cancel.flag,
+                retry: &config.retry,
+                multipart: &config.multipart,
+                priority: UploadPriority::Normal,
+                progress: Some(&count),
+            })
+        })
+        .collect();
+    futures::future::join_all(uploads.iter().map(|upload| {
+        upload_recording(upload, queue, &api, batch_id, managed_state, app_handle)
+    }))
+    .await;
+
+    let final_status = finalize_batch_status(managed_state, batch_id);
+    persist_state(managed_state).await?;
+    network::emit_backlog(app_handle, managed_state);
+    info!("Batch {} status: {:?}", batch_id, final_status);
+
+    Ok(())
 }
 
 #[tauri::command]
-pub fn cancel_import(batch_id: String) -> Result<(), AppError> {
+pub fn cancel_import(batch_id: String, queue: State<'_, ManagedQueue>) -> Result<(), AppError> {
     let flags = CANCEL_FLAGS.lock().unwrap();
     if let Some(flag) = flags.get(&batch_id) {
         flag.store(true, Ordering::Relaxed);
+        queue.cancel_batch(&batch_id);
         info!("Cancellation requested for batch {}", batch_id);
         Ok(())
     } else {
@@ -1,9 +1,24 @@
 pub mod api_client;
+pub mod bandwidth;
 pub mod batches;
 pub mod config;
 pub mod converter;
+pub mod encoder;
 pub mod hasher;
+pub mod http;
 pub mod importer;
+pub mod network;
+pub mod pcm;
+pub mod probe;
+pub mod retry;
 pub mod scanner;
+pub mod splitter;
+pub mod stitcher;
+pub mod timestamps;
+pub mod tuning;
+pub mod upload_queue;
 pub mod uploader;
+pub mod vad;
+pub mod validator;
 pub mod volumes;
+pub mod waveform;
@@ -0,0 +1,265 @@
+use std::sync::{LazyLock, Mutex};
+
+use chrono::Utc;
+use log::{info, warn};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, State};
+
+use crate::commands::api_client;
+use crate::commands::http::{ApiClient, ManagedHttp};
+use crate::commands::importer::read_auth;
+use crate::config::read_config;
+use crate::error::AppError;
+use crate::state::{ManagedState, ManagedStateInner};
+
+/// Result of the last reachability probe, if any has run
+static LAST_PROBE: LazyLock<Mutex<Option<Probe>>> = LazyLock::new(|| Mutex::new(None));
+
+#[derive(Clone)]
+struct Probe {
+    online: bool,
+    checked_at: String,
+}
+
+/// Whether a failure means the server could not be reached at all: no
+/// connection, a timeout, or a gateway reporting the server unreachable.
+/// Files that fail this way wait for the network instead of failing. Proxy
+/// and certificate failures are not waited out, since they need the settings
+/// fixed.
+pub fn is_offline(err: &AppError) -> bool {
+    match err {
+        AppError::Http(e) => {
+            (e.is_connect() || e.is_timeout())
+                && matches!(
+                    diagnose(err),
+                    ConnectionProblem::Dns
+                        | ConnectionProblem::Timeout
+                        | ConnectionProblem::Unreachable
+                )
+        }
+        AppError::Api { status, .. } => matches!(*status, 502..=504),
+        AppError::Timeout(_) => true,
+        _ => false,
+    }
+}
+
+/// Check whether the server answers, and remember the outcome for the backlog
+pub async fn probe(api: &ApiClient) -> bool {
+    let online = api_client::probe_server(api).await.is_ok();
+    let mut last = LAST_PROBE.lock().unwrap();
+    if last.as_ref().map(|p| p.online) != Some(online) {
+        info!(
+            "Server {}",
+            if online { "reachable" } else { "unreachable" }
+        );
+    }
+    *last = Some(Probe {
+        online,
+        checked_at: Utc::now().to_rfc3339(),
+    });
+    online
+}
+
+/// Files parked until the server can be reached, across all batches
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OfflineBacklog {
+    pub batches: u32,
+    pub files: u32,
+    pub bytes: u64,
+    /// Outcome of the last probe; unset until one has run
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub online: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub checked_at: Option<String>,
+}
+
+pub fn offline_backlog(managed_state: &ManagedStateInner) -> OfflineBacklog {
+    let probe = LAST_PROBE.lock().unwrap().clone();
+    let mut backlog = OfflineBacklog {
+        batches: 0,
+        files: 0,
+        bytes: 0,
+        online: probe.as_ref().map(|p| p.online),
+        checked_at: probe.map(|p| p.checked_at),
+    };
+    let app_state = managed_state.inner.lock().unwrap();
+    for batch in app_state.batches.values() {
+        let mut waiting = batch
+            .files
+            .values()
+            .filter_map(|f| f.waiting_for_network.as_ref())
+            .peekable();
+        if waiting.peek().is_some() {
+            backlog.batches += 1;
+        }
+        for file in waiting {
+            backlog.files += 1;
+            backlog.bytes += file.size_bytes;
+        }
+    }
+    backlog
+}
+
+/// Tell the frontend how much waits for the network
+pub fn emit_backlog(app_handle: &AppHandle, managed_state: &ManagedStateInner) {
+    let _ = app_handle.emit("offline-backlog", offline_backlog(managed_state));
+}
+
+#[tauri::command]
+pub fn get_offline_backlog(state: State<'_, ManagedState>) -> OfflineBacklog {
+    offline_backlog(&state)
+}
+
+/// Why the server could not be reached, as far as the error tells
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum ConnectionProblem {
+    /// The proxy URL or a CA certificate file is unusable
+    InvalidSettings,
+    /// The server's name could not be resolved
+    Dns,
+    /// The proxy refused the tunnel or wants credentials
+    Proxy,
+    /// The certificate chain ends in a CA that is not trusted, as with a
+    /// TLS-inspecting proxy whose root has not been added
+    UntrustedCertificate,
+    /// The certificate is for a different host name
+    CertificateHostMismatch,
+    CertificateExpired,
+    /// The TLS handshake failed some other way
+    Tls,
+    Timeout,
+    /// Nothing answered, or the connection was refused or dropped
+    Unreachable,
+}
+
+/// Outcome of a connectivity check, for the settings page
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionCheck {
+    pub ok: bool,
+    /// Status the server answered with
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub problem: Option<ConnectionProblem>,
+    /// The error with all its causes, for support
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    /// Whether a proxy from the settings was used
+    pub via_proxy: bool,
+}
+
+/// Place a failed request in a `ConnectionProblem`. TLS libraries differ by
+/// platform and only describe certificate failures in their messages, so
+/// these are matched on the text of the error and its causes.
+pub fn diagnose(err: &AppError) -> ConnectionProblem {
+    let reqwest_err = match err {
+        AppError::InvalidInput(_) => return ConnectionProblem::InvalidSettings,
+        AppError::Timeout(_) => return ConnectionProblem::Timeout,
+        AppError::Http(e) => e,
+        _ => return ConnectionProblem::Unreachable,
+    };
+    let text = error_chain(reqwest_err).to_lowercase();
+    let mentions = |needles: &[&str]| needles.iter().any(|n| text.contains(n));
+
+    if mentions(&["tunnel error", "proxy"]) {
+        ConnectionProblem::Proxy
+    } else if mentions(&["dns error", "failed to lookup", "nodename nor servname"]) {
+        ConnectionProblem::Dns
+    } else if mentions(&["expired"]) {
+        ConnectionProblem::CertificateExpired
+    } else if mentions(&[
+        "address mismatch",
+        "hostname mismatch",
+        "not valid for",
+        "does not match",
+        "doesn't match",
+    ]) {
+        ConnectionProblem::CertificateHostMismatch
+    } else if mentions(&[
+        "certificate verify failed",
+        "not trusted",
+        "self signed",
+        "self-signed",
+        "unknown issuer",
+        "unknownissuer",
+        "local issuer",
+        "invalid certificate chain",
+    ]) {
+        ConnectionProblem::UntrustedCertificate
+    } else if mentions(&["certificate", "handshake", "tls", "ssl"]) {
+        ConnectionProblem::Tls
+    } else if reqwest_err.is_timeout() {
+        ConnectionProblem::Timeout
+    } else {
+        ConnectionProblem::Unreachable
+    }
+}
+
+/// An error followed by its causes, joined like `a: b: c`
+fn error_chain(err: &dyn std::error::Error) -> String {
+    let mut text = err.to_string();
+    let mut source = err.source();
+    while let Some(cause) = source {
+        let cause_text = cause.to_string();
+        // Some errors repeat their cause in their own message
+        if !text.contains(&cause_text) {
+            text.push_str(": ");
+            text.push_str(&cause_text);
+        }
+        source = cause.source();
+    }
+    text
+}
+
+/// Try the saved server settings and explain what stops them working
+#[tauri::command]
+pub async fn check_connection(
+    app_handle: AppHandle,
+    http: State<'_, ManagedHttp>,
+) -> Result<ConnectionCheck, AppError> {
+    let config = read_config().await?;
+    let via_proxy = config
+        .http
+        .proxy_url
+        .as_deref()
+        .is_some_and(|u| !u.trim().is_empty());
+    let failed = |err: AppError| {
+        let problem = diagnose(&err);
+        let detail = match &err {
+            AppError::Http(e) => error_chain(e),
+            _ => err.to_string(),
+        };
+        warn!("Connection check failed ({:?}): {}", problem, detail);
+        ConnectionCheck {
+            ok: false,
+            status: None,
+            problem: Some(problem),
+            detail: Some(detail),
+            via_proxy,
+        }
+    };
+
+    let api = match http.client(&config, &read_auth(&app_handle)) {
+        Ok(api) => api,
+        Err(e) => return Ok(failed(e)),
+    };
+    Ok(match api_client::probe_server(&api).await {
+        Ok(status) => {
+            info!(
+                "Connection check reached {} ({})",
+                config.server_url, status
+            );
+            ConnectionCheck {
+                ok: true,
+                status: Some(status),
+                problem: None,
+                detail: None,
+                via_proxy,
+            }
+        }
+        Err(e) => failed(e),
+    })
+}
@@ -0,0 +1,182 @@
+use std::fs::File;
+use std::path::Path;
+use std::process::Stdio;
+
+use log::warn;
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::DecoderOptions;
+use symphonia::core::errors::Error as SymphoniaError;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+use tokio::io::AsyncReadExt;
+use tokio::process::Command;
+
+use crate::commands::converter::extended_path;
+use crate::error::AppError;
+
+/// Rate ffmpeg resamples to when symphonia can't decode the file
+const FFMPEG_RATE: u64 = 16_000;
+
+/// Consumes a recording as a stream of mono samples in -1.0..=1.0
+pub trait MonoSink {
+    fn push(&mut self, sample: f32);
+}
+
+/// Decode a recording to mono and feed it to the sink `make_sink` builds for
+/// its sample rate. Decodes natively, falling back to ffmpeg at 16 kHz for
+/// formats symphonia can't decode.
+pub async fn decode_mono<S, F>(
+    path: &str,
+    ffmpeg_path: Option<&str>,
+    make_sink: F,
+) -> Result<S, AppError>
+where
+    S: MonoSink + Send + 'static,
+    F: Fn(u64) -> S + Clone + Send + 'static,
+{
+    let native = {
+        let path = path.to_string();
+        let make_sink = make_sink.clone();
+        tokio::task::spawn_blocking(move || decode_native(Path::new(&path), make_sink))
+            .await
+            .map_err(|e| AppError::Other(e.to_string()))?
+    };
+
+    match (native, ffmpeg_path) {
+        (Ok(sink), _) => Ok(sink),
+        (Err(e), Some(ffmpeg)) => {
+            warn!("Native decoding failed for {}, using ffmpeg: {}", path, e);
+            decode_ffmpeg(path, ffmpeg, make_sink(FFMPEG_RATE)).await
+        }
+        (Err(e), None) => Err(e),
+    }
+}
+
+fn decode_native<S: MonoSink>(path: &Path, make_sink: impl Fn(u64) -> S) -> Result<S, AppError> {
+    let file = File::open(path)?;
+    let mss = MediaSourceStream::new(Box::new(file), Default::default());
+    let mut hint = Hint::new();
+    if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
+        hint.with_extension(ext);
+    }
+
+    let mut format = symphonia::default::get_probe()
+        .format(
+            &hint,
+            mss,
+            &FormatOptions::default(),
+            &MetadataOptions::default(),
+        )
+        .map_err(|e| AppError::InvalidInput(format!("Unrecognized audio format: {}", e)))?
+        .format;
+    let track = format
+        .default_track()
+        .ok_or_else(|| AppError::InvalidInput("No audio track".into()))?;
+    let track_id = track.id;
+    let sample_rate = track
+        .codec_params
+        .sample_rate
+        .ok_or_else(|| AppError::InvalidInput("Unknown sample rate".into()))?
+        as u64;
+    let mut decoder = symphonia::default::get_codecs()
+        .make(&track.codec_params, &DecoderOptions::default())
+        .map_err(|e| AppError::InvalidInput(format!("Unsupported codec: {}", e)))?;
+
+    let mut sink = make_sink(sample_rate);
+    let mut sample_buf: Option<SampleBuffer<f32>> = None;
+
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            Err(SymphoniaError::IoError(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+                break
+            }
+            Err(e) => return Err(AppError::Other(format!("Demux error: {}", e))),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+        let decoded = match decoder.decode(&packet) {
+            Ok(decoded) => decoded,
+            // Skip corrupt frames, as ffmpeg does
+            Err(SymphoniaError::DecodeError(_)) => continue,
+            Err(e) => return Err(AppError::Other(format!("Decode error: {}", e))),
+        };
+
+        let channels = decoded.spec().channels.count().max(1);
+        let buf = sample_buf
+            .get_or_insert_with(|| SampleBuffer::new(decoded.capacity() as u64, *decoded.spec()));
+        if buf.capacity() < decoded.capacity() * channels {
+            *buf = SampleBuffer::new(decoded.capacity() as u64, *decoded.spec());
+        }
+        buf.copy_interleaved_ref(decoded);
+
+        for frame in buf.samples().chunks(channels) {
+            sink.push(frame.iter().sum::<f32>() / channels as f32);
+        }
+    }
+
+    Ok(sink)
+}
+
+/// Decode to 16 kHz mono PCM with ffmpeg and feed it to the sink as it streams
+async fn decode_ffmpeg<S: MonoSink>(path: &str, ffmpeg: &str, mut sink: S) -> Result<S, AppError> {
+    let rate = FFMPEG_RATE.to_string();
+    let mut child = Command::new(ffmpeg)
+        .args([
+            "-v", "error", "-i", path, "-ac", "1", "-ar", &rate, "-f", "s16le", "-",
+        ])
+        .env("PATH", extended_path())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()?;
+
+    let mut stdout = child
+        .stdout
+        .take()
+        .ok_or_else(|| AppError::Ffmpeg("ffmpeg stdout unavailable".into()))?;
+    let mut stderr = child
+        .stderr
+        .take()
+        .ok_or_else(|| AppError::Ffmpeg("ffmpeg stderr unavailable".into()))?;
+    let stderr_task = tokio::spawn(async move {
+        let mut text = String::new();
+        let _ = stderr.read_to_string(&mut text).await;
+        text
+    });
+
+    let mut buf = vec![0u8; 64 * 1024];
+    // Low byte of a sample split across two reads
+    let mut carry: Option<u8> = None;
+    loop {
+        let n = stdout.read(&mut buf).await?;
+        if n == 0 {
+            break;
+        }
+        let mut bytes = &buf[..n];
+        if let Some(low) = carry.take() {
+            sink.push(i16::from_le_bytes([low, bytes[0]]) as f32 / 32768.0);
+            bytes = &bytes[1..];
+        }
+        let mut pairs = bytes.chunks_exact(2);
+        for pair in &mut pairs {
+            sink.push(i16::from_le_bytes([pair[0], pair[1]]) as f32 / 32768.0);
+        }
+        carry = pairs.remainder().first().copied();
+    }
+
+    let status = child.wait().await?;
+    let stderr = stderr_task.await.unwrap_or_default();
+    if !status.success() {
+        return Err(AppError::Ffmpeg(format!(
+            "ffmpeg decode failed (exit {}): {}",
+            status.code().unwrap_or(-1),
+            stderr
+        )));
+    }
+
+    Ok(sink)
+}
@@ -0,0 +1,190 @@
+use std::fs::File;
+use std::path::Path;
+
+use log::warn;
+use serde::{Deserialize, Serialize};
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+use tokio::process::Command;
+
+use crate::commands::converter::{extended_path, ffprobe_path};
+use crate::error::AppError;
+
+/// Stream properties of an audio file, as sent to the server in preflight.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AudioProbe {
+    pub mime_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub codec: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sample_rate: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub channels: Option<u16>,
+}
+
+impl AudioProbe {
+    pub fn is_empty(&self) -> bool {
+        self.duration_ms == Some(0)
+    }
+}
+
+/// MIME type by extension, matching the server's preflight mapping.
+pub fn mime_type_for(file_name: &str) -> &'static str {
+    let ext = Path::new(file_name)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())
+        .unwrap_or_default();
+
+    match ext.as_str() {
+        "wma" => "audio/x-ms-wma",
+        "wav" => "audio/wav",
+        "mp3" => "audio/mpeg",
+        "m4a" => "audio/mp4",
+        "flac" => "audio/flac",
+        "ogg" => "audio/ogg",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Probe an audio file: demux natively (WAV/MP3/FLAC/OGG/M4A), falling back to
+/// ffprobe for containers symphonia can't read (e.g. WMA) or when the duration
+/// isn't recorded in the headers.
+#[tauri::command]
+pub async fn probe_audio(path: String, ffmpeg_path: Option<String>) -> Result<AudioProbe, AppError> {
+    let native = {
+        let path = path.clone();
+        tokio::task::spawn_blocking(move || probe_native(Path::new(&path)))
+            .await
+            .map_err(|e| AppError::Other(e.to_string()))?
+    };
+
+    match native {
+        Ok(probe) if probe.duration_ms.is_some() => Ok(probe),
+        native => {
+            let ffmpeg = ffmpeg_path.unwrap_or_else(|| "ffmpeg".to_string());
+            match probe_ffprobe(&path, &ffprobe_path(&ffmpeg)).await {
+                Ok(probe) => Ok(probe),
+                Err(e) => {
+                    warn!("ffprobe fallback failed for {}: {}", path, e);
+                    native
+                }
+            }
+        }
+    }
+}
+
+fn probe_native(path: &Path) -> Result<AudioProbe, AppError> {
+    let file_name = path
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+
+    let file = File::open(path)?;
+    let mss = MediaSourceStream::new(Box::new(file), Default::default());
+    let mut hint = Hint::new();
+    if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
+        hint.with_extension(ext);
+    }
+
+    let probed = symphonia::default::get_probe()
+        .format(&hint, mss, &FormatOptions::default(), &MetadataOptions::default())
+        .map_err(|e| AppError::InvalidInput(format!("Unrecognized audio format: {}", e)))?;
+
+    let track = probed
+        .format
+        .default_track()
+        .ok_or_else(|| AppError::InvalidInput(format!("No audio track in {}", file_name)))?;
+    let params = &track.codec_params;
+
+    let duration_ms = match (params.time_base, params.n_frames, params.sample_rate) {
+        (Some(tb), Some(frames), _) => {
+            let time = tb.calc_time(frames);
+            Some(time.seconds * 1000 + (time.frac * 1000.0).round() as u64)
+        }
+        (None, Some(frames), Some(rate)) if rate > 0 => Some(frames * 1000 / rate as u64),
+        _ => None,
+    };
+
+    let codec = symphonia::default::get_codecs()
+        .get_codec(params.codec)
+        .map(|d| d.short_name.to_string());
+
+    Ok(AudioProbe {
+        mime_type: mime_type_for(&file_name).to_string(),
+        duration_ms,
+        codec,
+        sample_rate: params.sample_rate,
+        channels: params.channels.map(|c| c.count() as u16),
+    })
+}
+
+#[derive(Deserialize)]
+struct FfprobeOutput {
+    #[serde(default)]
+    streams: Vec<FfprobeStream>,
+    format: Option<FfprobeFormat>,
+}
+
+#[derive(Deserialize)]
+struct FfprobeStream {
+    codec_name: Option<String>,
+    sample_rate: Option<String>,
+    channels: Option<u16>,
+}
+
+#[derive(Deserialize)]
+struct FfprobeFormat {
+    duration: Option<String>,
+}
+
+async fn probe_ffprobe(path: &str, ffprobe: &str) -> Result<AudioProbe, AppError> {
+    let output = Command::new(ffprobe)
+        .args([
+            "-v",
+            "error",
+            "-select_streams",
+            "a:0",
+            "-show_entries",
+            "format=duration:stream=codec_name,sample_rate,channels",
+            "-of",
+            "json",
+            path,
+        ])
+        .env("PATH", extended_path())
+        .output()
+        .await?;
+
+    if !output.status.success() {
+        return Err(AppError::Ffmpeg(format!(
+            "ffprobe failed (exit {}): {}",
+            output.status.code().unwrap_or(-1),
+            String::from_utf8_lossy(&output.stderr)
+        )));
+    }
+
+    let parsed: FfprobeOutput = serde_json::from_slice(&output.stdout)?;
+    let stream = parsed.streams.into_iter().next();
+    let duration_ms = parsed
+        .format
+        .and_then(|f| f.duration)
+        .and_then(|d| d.parse::<f64>().ok())
+        .map(|secs| (secs * 1000.0).round() as u64);
+
+    Ok(AudioProbe {
+        mime_type: mime_type_for(path).to_string(),
+        duration_ms,
+        codec: stream.as_ref().and_then(|s| s.codec_name.clone()),
+        sample_rate: stream
+            .as_ref()
+            .and_then(|s| s.sample_rate.as_deref())
+            .and_then(|r| r.parse().ok()),
+        channels: stream.and_then(|s| s.channels),
+    })
+}
@@ -0,0 +1,117 @@
+use std::future::Future;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use log::warn;
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+
+use crate::config::RetryConfig;
+use crate::error::AppError;
+
+/// A failed attempt that is about to be tried again
+pub struct RetryAttempt<'a> {
+    /// Number of the attempt about to start; the first retry is attempt 2
+    pub attempt: u32,
+    pub delay: Duration,
+    pub error: &'a AppError,
+}
+
+/// Whether a failure may go away when tried again: dropped connections,
+/// timeouts, server errors and rate limiting. Other 4xx responses, local I/O
+/// errors and cancellation are final.
+pub fn is_retryable(err: &AppError) -> bool {
+    match err {
+        AppError::Http(e) => e.is_timeout() || e.is_connect() || e.is_request() || e.is_body(),
+        AppError::Api { status, .. } => matches!(*status, 408 | 429 | 500..=599),
+        AppError::Timeout(_) => true,
+        _ => false,
+    }
+}
+
+/// Wait requested by a `Retry-After` header, given in seconds or as an HTTP date
+pub fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let at = DateTime::parse_from_rfc2822(value).ok()?;
+    (at.with_timezone(&Utc) - Utc::now()).to_std().ok()
+}
+
+/// Run `op` until it succeeds, fails for good, or runs out of attempts.
+/// Between attempts it waits for the server's `Retry-After`, or else an
+/// exponential backoff with jitter; `on_retry` hears about each retry first.
+pub async fn with_retry<T, Fut>(
+    config: &RetryConfig,
+    what: &str,
+    on_retry: impl FnMut(&RetryAttempt),
+    op: impl FnMut() -> Fut,
+) -> Result<T, AppError>
+where
+    Fut: Future<Output = Result<T, AppError>>,
+{
+    with_retry_when(config, what, is_retryable, on_retry, op).await
+}
+
+/// `with_retry` for the failures `retryable` picks instead of `is_retryable`
+pub async fn with_retry_when<T, Fut>(
+    config: &RetryConfig,
+    what: &str,
+    retryable: impl Fn(&AppError) -> bool,
+    mut on_retry: impl FnMut(&RetryAttempt),
+    mut op: impl FnMut() -> Fut,
+) -> Result<T, AppError>
+where
+    Fut: Future<Output = Result<T, AppError>>,
+{
+    let mut attempt = 1;
+    loop {
+        let err = match op().await {
+            Ok(value) => return Ok(value),
+            Err(e) if attempt < config.max_attempts && retryable(&e) => e,
+            Err(e) => return Err(e),
+        };
+
+        let delay = match &err {
+            AppError::Api {
+                retry_after: Some(wait),
+                ..
+            } => *wait,
+            _ => backoff(config, attempt),
+        }
+        .min(Duration::from_millis(config.max_delay_ms));
+        attempt += 1;
+
+        warn!(
+            "{} failed, attempt {}/{} in {} ms: {}",
+            what,
+            attempt,
+            config.max_attempts,
+            delay.as_millis(),
+            err
+        );
+        on_retry(&RetryAttempt {
+            attempt,
+            delay,
+            error: &err,
+        });
+        tokio::time::sleep(delay).await;
+    }
+}
+
+/// Delay after failed attempt `attempt`: the initial delay doubled per retry,
+/// capped, then drawn from its upper half so parallel parts don't retry in step
+fn backoff(config: &RetryConfig, attempt: u32) -> Duration {
+    let ceiling = config
+        .initial_delay_ms
+        .saturating_mul(1 << (attempt - 1).min(16))
+        .min(config.max_delay_ms);
+    let half = ceiling / 2;
+    Duration::from_millis(half + random_below(ceiling - half + 1))
+}
+
+fn random_below(bound: u64) -> u64 {
+    use std::collections::hash_map::RandomState;
+    use std::hash::BuildHasher;
+    RandomState::new().hash_one(std::time::SystemTime::now()) % bound
+}
@@ -1,4 +1,4 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::path::Path;
 use walkdir::WalkDir;
 
@@ -81,3 +81,137 @@ pub async fn scan_files(dir_path: String) -> Result<Vec<FileInfo>, AppError> {
 
     Ok(results)
 }
+
+/// Position of a file within a recorder auto-split sequence, sent to the server
+/// so the parts can be reassembled in order.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SequenceInfo {
+    /// Shared by all parts; derived from the first part's hash so re-imports agree
+    pub sequence_id: String,
+    /// 1-based
+    pub sequence_index: u32,
+    pub sequence_count: u32,
+}
+
+/// Maximum gap between the end of one part and the start of the next. Covers
+/// FAT's 2-second mtime resolution and the recorder reopening the file.
+const SEQUENCE_GAP_TOLERANCE_MS: i64 = 5_000;
+
+/// Timing of a copied file, used to tell recorder auto-splits apart from
+/// separate sessions that happen to share a naming scheme.
+#[derive(Debug, Clone)]
+pub struct SequenceCandidate {
+    /// Path on the card; parts are only linked within one folder
+    pub path: String,
+    pub name: String,
+    /// Recording start time in ms, when known from metadata or the file name
+    pub start_ms: Option<i64>,
+    /// Last-modified time in ms; recorders write this when closing a part
+    pub modified_ms: i64,
+    pub duration_ms: Option<u64>,
+}
+
+/// `REC001_02.WAV` -> ("REC001", 2, true, "wav"); `ZOOM0002.WAV` -> ("ZOOM", 2, false, "wav").
+/// The flag is set when the counter is separated from a base that carries its own
+/// number, which is how recorders name the parts of one session.
+fn split_counter(name: &str) -> Option<(String, u32, bool, String)> {
+    let path = Path::new(name);
+    let stem = path.file_stem()?.to_str()?;
+    let ext = path.extension()?.to_str()?.to_lowercase();
+
+    let digits = stem.chars().rev().take_while(|c| c.is_ascii_digit()).count();
+    if digits == 0 || digits > 4 || digits == stem.len() {
+        return None;
+    }
+    let (head, counter) = stem.split_at(stem.len() - digits);
+    let counter: u32 = counter.parse().ok()?;
+
+    match head.strip_suffix(['_', '-']) {
+        Some(base) if !base.is_empty() => {
+            let is_part_suffix = base.ends_with(|c: char| c.is_ascii_digit());
+            Some((base.to_uppercase(), counter, is_part_suffix, ext))
+        }
+        _ => Some((head.to_uppercase(), counter, false, ext)),
+    }
+}
+
+/// Whether `next` starts where `prev` ends. `None` when there isn't enough
+/// timing information to tell.
+fn is_contiguous(prev: &SequenceCandidate, next: &SequenceCandidate) -> Option<bool> {
+    let mut known = false;
+
+    // Start of next == start of prev + prev's duration
+    if let (Some(prev_start), Some(next_start), Some(prev_dur)) =
+        (prev.start_ms, next.start_ms, prev.duration_ms)
+    {
+        known = true;
+        if (next_start - (prev_start + prev_dur as i64)).abs() <= SEQUENCE_GAP_TOLERANCE_MS {
+            return Some(true);
+        }
+    }
+
+    // Each part is closed as the next one opens, so mtimes differ by next's duration
+    if let Some(next_dur) = next.duration_ms {
+        if prev.modified_ms > 0 && next.modified_ms > 0 {
+            known = true;
+            let gap = next.modified_ms - next_dur as i64 - prev.modified_ms;
+            if gap.abs() <= SEQUENCE_GAP_TOLERANCE_MS {
+                return Some(true);
+            }
+        }
+    }
+
+    known.then_some(false)
+}
+
+/// Find runs of files a recorder split from one continuous session. Returns
+/// the indices of each run (two or more files) in recording order.
+///
+/// Files are linked when they sit in the same folder, share a base name and
+/// extension with consecutive counters, and their timing is contiguous.
+/// Names with an explicit part suffix (`REC001_01`) are also linked when
+/// timing is unknown, but never when it shows a gap.
+pub fn detect_sequences(files: &[SequenceCandidate]) -> Vec<Vec<usize>> {
+    let mut keyed: Vec<(String, String, String, u32, bool, usize)> = files
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, f)| {
+            let (base, counter, is_part_suffix, ext) = split_counter(&f.name)?;
+            let dir = Path::new(&f.path)
+                .parent()
+                .map(|p| p.to_string_lossy().to_string())
+                .unwrap_or_default();
+            Some((dir, base, ext, counter, is_part_suffix, idx))
+        })
+        .collect();
+    keyed.sort();
+
+    let mut sequences = Vec::new();
+    let mut current: Vec<usize> = Vec::new();
+
+    for pair in keyed.windows(2) {
+        let (prev, next) = (&pair[0], &pair[1]);
+        let same_series = prev.0 == next.0 && prev.1 == next.1 && prev.2 == next.2;
+        let linked = same_series
+            && next.3 == prev.3 + 1
+            && match is_contiguous(&files[prev.5], &files[next.5]) {
+                Some(contiguous) => contiguous,
+                None => prev.4 && next.4,
+            };
+
+        if linked {
+            if current.is_empty() {
+                current.push(prev.5);
+            }
+            current.push(next.5);
+        } else if !current.is_empty() {
+            sequences.push(std::mem::take(&mut current));
+        }
+    }
+    if !current.is_empty() {
+        sequences.push(current);
+    }
+
+    sequences
+}
@@ -0,0 +1,437 @@
+use std::fs::File;
+use std::io::{BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use log::warn;
+use serde::{Deserialize, Serialize};
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::DecoderOptions;
+use symphonia::core::errors::Error as SymphoniaError;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+use tokio::process::Command;
+
+use crate::commands::converter::extended_path;
+use crate::commands::stitcher::read_wav_part;
+use crate::config::ChunkingConfig;
+use crate::error::AppError;
+
+/// Window over which the signal level is measured for silence detection
+const LEVEL_WINDOW_MS: u64 = 10;
+/// Cut points are searched for within this distance of each chunk boundary
+const SEARCH_WINDOW_MS: u64 = 20_000;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SilenceRegion {
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub duration_ms: u64,
+}
+
+/// One piece of a recording split for transcription. Matches the CLI
+/// importer's `ChunkInfo`; chunks overlap by the configured overlap.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChunkInfo {
+    pub index: u32,
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub path: String,
+}
+
+/// Find stretches quieter than `threshold_db` (dBFS) lasting at least
+/// `min_duration_ms`. Decodes natively, falling back to ffmpeg's
+/// `silencedetect` filter for formats symphonia can't decode.
+pub async fn detect_silence(
+    path: &str,
+    threshold_db: f64,
+    min_duration_ms: u64,
+    ffmpeg_path: Option<&str>,
+) -> Result<Vec<SilenceRegion>, AppError> {
+    let native = {
+        let path = path.to_string();
+        tokio::task::spawn_blocking(move || {
+            detect_silence_native(Path::new(&path), threshold_db, min_duration_ms)
+        })
+        .await
+        .map_err(|e| AppError::Other(e.to_string()))?
+    };
+
+    match (native, ffmpeg_path) {
+        (Ok(regions), _) => Ok(regions),
+        (Err(e), Some(ffmpeg)) => {
+            warn!("Native silence detection failed for {}, using ffmpeg: {}", path, e);
+            detect_silence_ffmpeg(path, threshold_db, min_duration_ms, ffmpeg).await
+        }
+        (Err(e), None) => Err(e),
+    }
+}
+
+fn detect_silence_native(
+    path: &Path,
+    threshold_db: f64,
+    min_duration_ms: u64,
+) -> Result<Vec<SilenceRegion>, AppError> {
+    let file = File::open(path)?;
+    let mss = MediaSourceStream::new(Box::new(file), Default::default());
+    let mut hint = Hint::new();
+    if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
+        hint.with_extension(ext);
+    }
+
+    let mut format = symphonia::default::get_probe()
+        .format(&hint, mss, &FormatOptions::default(), &MetadataOptions::default())
+        .map_err(|e| AppError::InvalidInput(format!("Unrecognized audio format: {}", e)))?
+        .format;
+    let track = format
+        .default_track()
+        .ok_or_else(|| AppError::InvalidInput("No audio track".into()))?;
+    let track_id = track.id;
+    let sample_rate = track
+        .codec_params
+        .sample_rate
+        .ok_or_else(|| AppError::InvalidInput("Unknown sample rate".into()))?
+        as u64;
+    let mut decoder = symphonia::default::get_codecs()
+        .make(&track.codec_params, &DecoderOptions::default())
+        .map_err(|e| AppError::InvalidInput(format!("Unsupported codec: {}", e)))?;
+
+    // Mean square amplitude below this counts as silent
+    let threshold = 10f64.powf(threshold_db / 10.0);
+    let window_frames = (sample_rate * LEVEL_WINDOW_MS / 1000).max(1);
+
+    let mut regions = Vec::new();
+    let mut silence_start: Option<u64> = None;
+    let mut frames_seen = 0u64;
+    let mut window_sum = 0f64;
+    let mut window_samples = 0u64;
+    let mut window_len = 0u64;
+    let mut sample_buf: Option<SampleBuffer<f32>> = None;
+
+    // Called with the first frame of each completed window
+    let mut close_window = |start_frame: u64, sum: f64, samples: u64, regions: &mut Vec<SilenceRegion>| {
+        let silent = samples > 0 && sum / samples as f64 <= threshold;
+        let at_ms = start_frame * 1000 / sample_rate;
+        match (silent, silence_start) {
+            (true, None) => silence_start = Some(at_ms),
+            (false, Some(start)) => {
+                let end_ms = at_ms;
+                if end_ms - start >= min_duration_ms {
+                    regions.push(SilenceRegion {
+                        start_ms: start,
+                        end_ms,
+                        duration_ms: end_ms - start,
+                    });
+                }
+                silence_start = None;
+            }
+            _ => {}
+        }
+    };
+
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            Err(SymphoniaError::IoError(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(AppError::Other(format!("Demux error: {}", e))),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+        let decoded = match decoder.decode(&packet) {
+            Ok(decoded) => decoded,
+            // Skip corrupt frames, as ffmpeg does
+            Err(SymphoniaError::DecodeError(_)) => continue,
+            Err(e) => return Err(AppError::Other(format!("Decode error: {}", e))),
+        };
+
+        let channels = decoded.spec().channels.count().max(1);
+        let buf = sample_buf.get_or_insert_with(|| {
+            SampleBuffer::new(decoded.capacity() as u64, *decoded.spec())
+        });
+        if buf.capacity() < decoded.capacity() * channels {
+            *buf = SampleBuffer::new(decoded.capacity() as u64, *decoded.spec());
+        }
+        buf.copy_interleaved_ref(decoded);
+
+        for frame in buf.samples().chunks(channels) {
+            for &s in frame {
+                window_sum += (s as f64) * (s as f64);
+            }
+            window_samples += frame.len() as u64;
+            window_len += 1;
+            frames_seen += 1;
+            if window_len == window_frames {
+                close_window(frames_seen - window_len, window_sum, window_samples, &mut regions);
+                window_sum = 0.0;
+                window_samples = 0;
+                window_len = 0;
+            }
+        }
+    }
+
+    if window_len > 0 {
+        close_window(frames_seen - window_len, window_sum, window_samples, &mut regions);
+    }
+    // Trailing silence runs to the end of the file
+    if let Some(start) = silence_start {
+        let end_ms = frames_seen * 1000 / sample_rate;
+        if end_ms.saturating_sub(start) >= min_duration_ms {
+            regions.push(SilenceRegion {
+                start_ms: start,
+                end_ms,
+                duration_ms: end_ms - start,
+            });
+        }
+    }
+
+    Ok(regions)
+}
+
+async fn detect_silence_ffmpeg(
+    path: &str,
+    threshold_db: f64,
+    min_duration_ms: u64,
+    ffmpeg: &str,
+) -> Result<Vec<SilenceRegion>, AppError> {
+    let filter = format!(
+        "silencedetect=noise={}dB:d={}",
+        threshold_db,
+        min_duration_ms as f64 / 1000.0
+    );
+    let output = Command::new(ffmpeg)
+        .args(["-i", path, "-af", &filter, "-f", "null", "-"])
+        .env("PATH", extended_path())
+        .output()
+        .await?;
+
+    if !output.status.success() {
+        return Err(AppError::Ffmpeg(format!(
+            "ffmpeg silencedetect failed (exit {}): {}",
+            output.status.code().unwrap_or(-1),
+            String::from_utf8_lossy(&output.stderr)
+        )));
+    }
+
+    Ok(parse_silencedetect(&String::from_utf8_lossy(&output.stderr)))
+}
+
+/// Parse `silence_start: 12.3` / `silence_end: 14.1 | silence_duration: 1.8` lines.
+fn parse_silencedetect(stderr: &str) -> Vec<SilenceRegion> {
+    let to_ms = |s: &str| s.trim().parse::<f64>().ok().map(|v| (v * 1000.0).round() as u64);
+    let field = |line: &str, key: &str| -> Option<u64> {
+        let rest = &line[line.find(key)? + key.len()..];
+        to_ms(rest.split('|').next()?)
+    };
+
+    let mut regions = Vec::new();
+    let mut current_start = None;
+    for line in stderr.lines() {
+        if let Some(start) = field(line, "silence_start:") {
+            current_start = Some(start);
+            continue;
+        }
+        if let (Some(end_ms), Some(duration_ms), Some(start_ms)) = (
+            field(line, "silence_end:"),
+            field(line, "silence_duration:"),
+            current_start,
+        ) {
+            regions.push(SilenceRegion {
+                start_ms,
+                end_ms,
+                duration_ms,
+            });
+            current_start = None;
+        }
+    }
+    regions
+}
+
+/// Choose chunk start times so chunks are at most `max_chunk_ms` long, cutting in
+/// the middle of the silence nearest each boundary. Each cut is moved back by
+/// `overlap_ms` so speech at the boundary appears in both chunks.
+pub fn find_cut_points(
+    total_duration_ms: u64,
+    max_chunk_ms: u64,
+    silences: &[SilenceRegion],
+    overlap_ms: u64,
+) -> Vec<u64> {
+    let mut cut_points = vec![0];
+    let mut current_pos = 0u64;
+
+    while current_pos + max_chunk_ms < total_duration_ms {
+        let target = current_pos + max_chunk_ms;
+        let window_start = target.saturating_sub(SEARCH_WINDOW_MS);
+        let window_end = target + SEARCH_WINDOW_MS;
+
+        let best = silences
+            .iter()
+            .map(|s| (s, (s.start_ms + s.end_ms) / 2))
+            .filter(|&(_, mid)| mid >= window_start && mid <= window_end)
+            .min_by_key(|&(_, mid)| mid.abs_diff(target));
+
+        match best {
+            // Only accept cuts that move forward, so short max_chunk_ms values always terminate
+            Some((_, cut_at)) if cut_at > current_pos => {
+                cut_points.push(cut_at.saturating_sub(overlap_ms).max(current_pos));
+                current_pos = cut_at;
+            }
+            _ => {
+                cut_points.push(target.saturating_sub(overlap_ms));
+                current_pos = target;
+            }
+        }
+    }
+
+    cut_points
+}
+
+/// Split `path` into chunks of at most the configured length (plus overlap),
+/// cut at silences, written next to it as `<stem>_chunkNNN.<ext>`. WAV is
+/// sliced natively; other formats are cut by ffmpeg with stream copy, so chunks
+/// keep the uploaded file's format. Returns a single chunk covering the whole
+/// file when no split is needed.
+pub async fn split_audio(
+    path: &str,
+    duration_ms: u64,
+    settings: &ChunkingConfig,
+    ffmpeg_path: Option<&str>,
+) -> Result<Vec<ChunkInfo>, AppError> {
+    let max_chunk_ms = settings.max_chunk_minutes.max(1) * 60_000;
+    let overlap_ms = settings.overlap_ms;
+    if duration_ms <= max_chunk_ms {
+        return Ok(vec![ChunkInfo {
+            index: 0,
+            start_ms: 0,
+            end_ms: duration_ms,
+            path: path.to_string(),
+        }]);
+    }
+
+    let silences = detect_silence(
+        path,
+        settings.silence_threshold_db,
+        settings.min_silence_ms,
+        ffmpeg_path,
+    )
+    .await?;
+    let cut_points = find_cut_points(duration_ms, max_chunk_ms, &silences, overlap_ms);
+
+    let source = Path::new(path);
+    let stem = source.file_stem().unwrap_or_default().to_string_lossy().to_string();
+    let ext = source
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())
+        .unwrap_or_default();
+    let dir = source.parent().unwrap_or(Path::new("."));
+
+    let mut chunks = Vec::new();
+    for (i, &start_ms) in cut_points.iter().enumerate() {
+        let end_ms = cut_points
+            .get(i + 1)
+            .map(|next| next + overlap_ms)
+            .unwrap_or(duration_ms)
+            .min(duration_ms);
+        let chunk_path = dir
+            .join(format!("{}_chunk{:03}.{}", stem, i, ext))
+            .to_string_lossy()
+            .to_string();
+
+        if ext == "wav" {
+            let (src, dst) = (path.to_string(), chunk_path.clone());
+            tokio::task::spawn_blocking(move || slice_wav(Path::new(&src), Path::new(&dst), start_ms, end_ms))
+                .await
+                .map_err(|e| AppError::Other(e.to_string()))??;
+        } else {
+            let ffmpeg = ffmpeg_path.ok_or_else(|| {
+                AppError::Ffmpeg(format!("ffmpeg is required to split .{} files", ext))
+            })?;
+            cut_ffmpeg(path, &chunk_path, start_ms, end_ms, ffmpeg).await?;
+        }
+
+        chunks.push(ChunkInfo {
+            index: i as u32,
+            start_ms,
+            end_ms,
+            path: chunk_path,
+        });
+    }
+
+    Ok(chunks)
+}
+
+/// Copy the frames between `start_ms` and `end_ms` into a new WAV file.
+fn slice_wav(source: &Path, dest: &Path, start_ms: u64, end_ms: u64) -> Result<(), AppError> {
+    let part = read_wav_part(source)?;
+    let fmt = &part.fmt;
+    if fmt.len() < 16 {
+        return Err(AppError::InvalidInput("Truncated fmt chunk".into()));
+    }
+    let byte_rate = u32::from_le_bytes(fmt[8..12].try_into().unwrap()) as u64;
+    let block_align = u16::from_le_bytes([fmt[12], fmt[13]]).max(1) as u64;
+    let offset_of = |ms: u64| {
+        let bytes = (ms * byte_rate / 1000).min(part.data_size);
+        bytes - bytes % block_align
+    };
+    let (start, end) = (offset_of(start_ms), offset_of(end_ms));
+    let data_size = end - start;
+
+    let fmt_chunk_len = 8 + fmt.len() as u64 + (fmt.len() as u64 & 1);
+    let riff_size = 4 + fmt_chunk_len + 8 + data_size + (data_size & 1);
+
+    let mut out = BufWriter::new(File::create(dest)?);
+    out.write_all(b"RIFF")?;
+    out.write_all(&(riff_size as u32).to_le_bytes())?;
+    out.write_all(b"WAVE")?;
+    out.write_all(b"fmt ")?;
+    out.write_all(&(fmt.len() as u32).to_le_bytes())?;
+    out.write_all(fmt)?;
+    if fmt.len() % 2 == 1 {
+        out.write_all(&[0])?;
+    }
+    out.write_all(b"data")?;
+    out.write_all(&(data_size as u32).to_le_bytes())?;
+
+    let mut file = File::open(source)?;
+    file.seek(SeekFrom::Start(part.data_start + start))?;
+    std::io::copy(&mut file.take(data_size), &mut out)?;
+    if data_size % 2 == 1 {
+        out.write_all(&[0])?;
+    }
+
+    out.into_inner()
+        .map_err(|e| AppError::Io(e.into_error()))?
+        .sync_all()?;
+    Ok(())
+}
+
+async fn cut_ffmpeg(
+    input: &str,
+    output: &str,
+    start_ms: u64,
+    end_ms: u64,
+    ffmpeg: &str,
+) -> Result<(), AppError> {
+    let start = format!("{:.3}", start_ms as f64 / 1000.0);
+    let duration = format!("{:.3}", (end_ms - start_ms) as f64 / 1000.0);
+    let result = Command::new(ffmpeg)
+        .args(["-y", "-i", input, "-ss", &start, "-t", &duration, "-c", "copy", output])
+        .env("PATH", extended_path())
+        .output()
+        .await?;
+
+    if !result.status.success() {
+        let stderr = String::from_utf8_lossy(&result.stderr);
+        return Err(AppError::Ffmpeg(format!(
+            "ffmpeg split failed (exit {}): {}",
+            result.status.code().unwrap_or(-1),
+            stderr
+        )));
+    }
+
+    Ok(())
+}
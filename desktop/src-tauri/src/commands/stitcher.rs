@@ -0,0 +1,227 @@
+use std::fs::File;
+use std::io::{BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use tokio::process::Command;
+
+use crate::commands::converter::extended_path;
+use crate::error::AppError;
+
+/// Join the parts of a recorder auto-split sequence into `output` without
+/// re-encoding. WAV parts are joined natively; other formats go through
+/// ffmpeg's concat demuxer with stream copy, so `ffmpeg_path` must be set for
+/// them. A joined WAV past 4 GB is written as RF64, which only ffmpeg reads
+/// back, so without ffmpeg such a join is refused and the parts stay apart.
+pub async fn stitch_recordings(
+    parts: &[String],
+    output: &str,
+    ffmpeg_path: Option<&str>,
+) -> Result<(), AppError> {
+    let all_wav = parts.iter().all(|p| {
+        Path::new(p)
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| e.eq_ignore_ascii_case("wav"))
+    });
+
+    let result = if all_wav {
+        let paths = parts.to_vec();
+        let out = output.to_string();
+        let allow_rf64 = ffmpeg_path.is_some();
+        let native =
+            tokio::task::spawn_blocking(move || stitch_wav(&paths, Path::new(&out), allow_rf64))
+                .await
+                .map_err(|e| AppError::Other(e.to_string()))?;
+        match (native, ffmpeg_path) {
+            // RF64 parts can't be walked natively; let ffmpeg handle them
+            (Err(AppError::InvalidInput(_)), Some(ffmpeg)) => {
+                stitch_ffmpeg(parts, output, ffmpeg).await
+            }
+            (result, _) => result,
+        }
+    } else {
+        match ffmpeg_path {
+            Some(ffmpeg) => stitch_ffmpeg(parts, output, ffmpeg).await,
+            None => Err(AppError::Ffmpeg(
+                "ffmpeg is required to join non-WAV recordings".into(),
+            )),
+        }
+    };
+
+    if result.is_err() {
+        let _ = tokio::fs::remove_file(output).await;
+    }
+    result
+}
+
+/// Largest `fmt ` body: 16 bytes, a 2-byte extension size and the extension
+const MAX_FMT_LEN: u64 = 18 + u16::MAX as u64;
+
+/// Layout of a plain RIFF/WAVE file: the raw `fmt ` chunk body and the audio data range.
+pub(crate) struct WavPart {
+    pub fmt: Vec<u8>,
+    pub data_start: u64,
+    pub data_size: u64,
+}
+
+pub(crate) fn read_wav_part(path: &Path) -> Result<WavPart, AppError> {
+    let mut file = File::open(path)?;
+    let file_len = file.metadata()?.len();
+
+    let mut header = [0u8; 12];
+    file.read_exact(&mut header)?;
+    if &header[0..4] != b"RIFF" || &header[8..12] != b"WAVE" {
+        return Err(AppError::InvalidInput(format!(
+            "{} is not a RIFF/WAVE file",
+            path.display()
+        )));
+    }
+
+    let mut fmt = None;
+    let mut pos = 12u64;
+    while pos + 8 <= file_len {
+        file.seek(SeekFrom::Start(pos))?;
+        let mut chunk_header = [0u8; 8];
+        file.read_exact(&mut chunk_header)?;
+        let size = u32::from_le_bytes(chunk_header[4..8].try_into().unwrap()) as u64;
+
+        match &chunk_header[0..4] {
+            b"fmt " => {
+                // Check the claimed size before allocating for it
+                if size > MAX_FMT_LEN || pos + 8 + size > file_len {
+                    return Err(AppError::InvalidInput(format!(
+                        "{} has a corrupt fmt chunk",
+                        path.display()
+                    )));
+                }
+                let mut body = vec![0u8; size as usize];
+                file.read_exact(&mut body)?;
+                fmt = Some(body);
+            }
+            b"data" => {
+                let fmt = fmt.ok_or_else(|| {
+                    AppError::InvalidInput(format!("{} has no fmt chunk", path.display()))
+                })?;
+                return Ok(WavPart {
+                    fmt,
+                    data_start: pos + 8,
+                    data_size: size.min(file_len - pos - 8),
+                });
+            }
+            _ => {}
+        }
+        pos += 8 + size + (size & 1);
+    }
+
+    Err(AppError::InvalidInput(format!(
+        "{} has no data chunk",
+        path.display()
+    )))
+}
+
+fn stitch_wav(parts: &[String], output: &Path, allow_rf64: bool) -> Result<(), AppError> {
+    let layouts = parts
+        .iter()
+        .map(|p| read_wav_part(Path::new(p)))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let fmt = &layouts[0].fmt;
+    if fmt.len() < 16 {
+        return Err(AppError::InvalidInput("Truncated fmt chunk".into()));
+    }
+    if let Some(idx) = layouts.iter().position(|l| &l.fmt != fmt) {
+        return Err(AppError::Other(format!(
+            "{} has a different audio format from the first part",
+            parts[idx]
+        )));
+    }
+    let block_align = u16::from_le_bytes([fmt[12], fmt[13]]).max(1) as u64;
+
+    let fmt_chunk_len = 8 + fmt.len() as u64 + (fmt.len() as u64 & 1);
+    let data_size: u64 = layouts.iter().map(|l| l.data_size).sum();
+    let riff_size = 4 + fmt_chunk_len + 8 + data_size + (data_size & 1);
+    if riff_size > u32::MAX as u64 && !allow_rf64 {
+        return Err(AppError::Other(
+            "joined WAV would pass 4 GB, which needs ffmpeg to process".into(),
+        ));
+    }
+
+    let mut out = BufWriter::new(File::create(output)?);
+    if riff_size <= u32::MAX as u64 {
+        out.write_all(b"RIFF")?;
+        out.write_all(&(riff_size as u32).to_le_bytes())?;
+        out.write_all(b"WAVE")?;
+    } else {
+        // RF64 (EBU Tech 3306): real sizes live in ds64, the 32-bit fields are -1
+        let riff_size = riff_size + 8 + 28;
+        out.write_all(b"RF64")?;
+        out.write_all(&u32::MAX.to_le_bytes())?;
+        out.write_all(b"WAVE")?;
+        out.write_all(b"ds64")?;
+        out.write_all(&28u32.to_le_bytes())?;
+        out.write_all(&riff_size.to_le_bytes())?;
+        out.write_all(&data_size.to_le_bytes())?;
+        out.write_all(&(data_size / block_align).to_le_bytes())?;
+        out.write_all(&0u32.to_le_bytes())?;
+    }
+
+    out.write_all(b"fmt ")?;
+    out.write_all(&(fmt.len() as u32).to_le_bytes())?;
+    out.write_all(fmt)?;
+    if fmt.len() % 2 == 1 {
+        out.write_all(&[0])?;
+    }
+
+    out.write_all(b"data")?;
+    let data_field = u32::try_from(data_size).unwrap_or(u32::MAX);
+    out.write_all(&data_field.to_le_bytes())?;
+
+    for (path, layout) in parts.iter().zip(&layouts) {
+        let mut file = File::open(path)?;
+        file.seek(SeekFrom::Start(layout.data_start))?;
+        let copied = std::io::copy(&mut file.take(layout.data_size), &mut out)?;
+        if copied != layout.data_size {
+            return Err(AppError::Other(format!("{} ended early", path)));
+        }
+    }
+    if data_size % 2 == 1 {
+        out.write_all(&[0])?;
+    }
+
+    out.into_inner()
+        .map_err(|e| AppError::Io(e.into_error()))?
+        .sync_all()?;
+    Ok(())
+}
+
+async fn stitch_ffmpeg(parts: &[String], output: &str, ffmpeg: &str) -> Result<(), AppError> {
+    // concat demuxer list; single quotes inside paths are closed, escaped and reopened
+    let list: String = parts
+        .iter()
+        .map(|p| format!("file '{}'\n", p.replace('\'', r"'\''")))
+        .collect();
+    let list_path = format!("{}.concat.txt", output);
+    tokio::fs::write(&list_path, list).await?;
+
+    let result = Command::new(ffmpeg)
+        .args([
+            "-y", "-f", "concat", "-safe", "0", "-i", &list_path, "-c", "copy", "-map_metadata",
+            "0", output,
+        ])
+        .env("PATH", extended_path())
+        .output()
+        .await;
+    let _ = tokio::fs::remove_file(&list_path).await;
+    let result = result?;
+
+    if !result.status.success() {
+        let stderr = String::from_utf8_lossy(&result.stderr);
+        return Err(AppError::Ffmpeg(format!(
+            "ffmpeg concat failed (exit {}): {}",
+            result.status.code().unwrap_or(-1),
+            stderr
+        )));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,443 @@
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+use chrono::{DateTime, Duration, FixedOffset, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::commands::volumes::RecorderIdentifier;
+
+/// Where a recording's start time was taken from, in order of precedence.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum TimestampSource {
+    /// BWF `bext` chunk OriginationDate/OriginationTime
+    Bext,
+    /// RIFF `LIST/INFO` ICRD (creation date) tag
+    RiffInfo,
+    /// ID3v2 TDRC, or TYER+TDAT+TIME
+    Id3,
+    /// MP4 `mvhd` creation time
+    Mp4,
+    /// Recorder naming pattern such as `230415_1030.WAV`
+    FileName,
+    /// Filesystem modification time (least reliable; changes when files are touched)
+    FileModified,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum RecordingTime {
+    /// Recorder wall-clock time without zone information
+    Local(NaiveDateTime),
+    /// Absolute time, e.g. MP4 `mvhd` which is defined as UTC
+    Absolute(DateTime<Utc>),
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct RecordingTimestamp {
+    pub time: RecordingTime,
+    pub source: TimestampSource,
+}
+
+impl RecordingTimestamp {
+    /// Resolve to an absolute time using the recorder's timezone and clock offset.
+    /// Without a RECORDER_ID.json the recorder is assumed to share this machine's timezone.
+    pub fn resolve(&self, recorder: Option<&RecorderIdentifier>) -> Option<DateTime<FixedOffset>> {
+        match (self.time, recorder) {
+            (RecordingTime::Local(local), Some(rec)) => rec.resolve_local_time(local),
+            (RecordingTime::Local(local), None) => Local
+                .from_local_datetime(&local)
+                .earliest()
+                .map(|dt| dt.fixed_offset()),
+            (RecordingTime::Absolute(utc), rec) => {
+                let offset = rec.map(|r| r.clock_offset_seconds).unwrap_or(0);
+                Some((utc - Duration::seconds(offset)).fixed_offset())
+            }
+        }
+    }
+}
+
+/// Determine when a recording started. Embedded metadata wins over the file name,
+/// which wins over the filesystem mtime:
+/// `bext` > `LIST/INFO` ICRD > ID3 > MP4 `mvhd` > file name > mtime.
+/// `modified_ms` is 0 when the mtime could not be read; with nothing else to go
+/// on the start time is then unknown, rather than the epoch.
+pub fn extract_recording_timestamp(
+    path: &Path,
+    file_name: &str,
+    modified_ms: f64,
+) -> Option<RecordingTimestamp> {
+    let ext = Path::new(file_name)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())
+        .unwrap_or_default();
+
+    let embedded = match ext.as_str() {
+        "wav" => read_wav_timestamp(path),
+        "mp3" => read_id3_timestamp(path),
+        "m4a" => read_mp4_timestamp(path),
+        _ => None,
+    };
+
+    embedded
+        .or_else(|| {
+            parse_file_name_timestamp(file_name).map(|local| RecordingTimestamp {
+                time: RecordingTime::Local(local),
+                source: TimestampSource::FileName,
+            })
+        })
+        .or_else(|| {
+            // FAT stores local wall-clock time, which the OS reads back in this machine's
+            // timezone, so recover that naive time for reinterpretation in the recorder's zone.
+            let local = DateTime::from_timestamp_millis(modified_ms as i64)
+                .filter(|_| modified_ms > 0.0)?
+                .with_timezone(&Local)
+                .naive_local();
+            Some(RecordingTimestamp {
+                time: RecordingTime::Local(local),
+                source: TimestampSource::FileModified,
+            })
+        })
+}
+
+// ===== WAV (RIFF) =====
+
+const BEXT_DATE_OFFSET: usize = 320;
+const BEXT_TIME_OFFSET: usize = 330;
+const MAX_METADATA_CHUNK: u32 = 64 * 1024;
+
+fn read_wav_timestamp(path: &Path) -> Option<RecordingTimestamp> {
+    let mut file = File::open(path).ok()?;
+    let file_len = file.metadata().ok()?.len();
+
+    let mut header = [0u8; 12];
+    file.read_exact(&mut header).ok()?;
+    if !(&header[0..4] == b"RIFF" || &header[0..4] == b"RF64") || &header[8..12] != b"WAVE" {
+        return None;
+    }
+
+    let mut bext = None;
+    let mut icrd = None;
+    let mut pos = 12u64;
+
+    while pos + 8 <= file_len {
+        file.seek(SeekFrom::Start(pos)).ok()?;
+        let mut chunk_header = [0u8; 8];
+        file.read_exact(&mut chunk_header).ok()?;
+        let id = &chunk_header[0..4];
+        let size = u32::from_le_bytes(chunk_header[4..8].try_into().ok()?);
+
+        match id {
+            b"bext" if size <= MAX_METADATA_CHUNK => {
+                let data = read_exact_vec(&mut file, size as usize)?;
+                bext = parse_bext(&data);
+            }
+            b"LIST" if size <= MAX_METADATA_CHUNK => {
+                let data = read_exact_vec(&mut file, size as usize)?;
+                icrd = icrd.or_else(|| parse_list_info_icrd(&data));
+            }
+            // Streaming recorders write 0xFFFFFFFF; nothing after it can be located
+            b"data" if size == u32::MAX => break,
+            _ => {}
+        }
+
+        if bext.is_some() {
+            break;
+        }
+        pos += 8 + size as u64 + (size as u64 & 1);
+    }
+
+    bext.map(|local| RecordingTimestamp {
+        time: RecordingTime::Local(local),
+        source: TimestampSource::Bext,
+    })
+    .or_else(|| {
+        icrd.map(|local| RecordingTimestamp {
+            time: RecordingTime::Local(local),
+            source: TimestampSource::RiffInfo,
+        })
+    })
+}
+
+fn read_exact_vec(file: &mut File, len: usize) -> Option<Vec<u8>> {
+    let mut buf = vec![0u8; len];
+    file.read_exact(&mut buf).ok()?;
+    Some(buf)
+}
+
+fn parse_bext(data: &[u8]) -> Option<NaiveDateTime> {
+    let date = data.get(BEXT_DATE_OFFSET..BEXT_DATE_OFFSET + 10)?;
+    let time = data.get(BEXT_TIME_OFFSET..BEXT_TIME_OFFSET + 8)?;
+    // Separators vary between recorders ("-", ":", "/", " "), so only digits are used
+    let digits: Vec<u32> = digit_runs(&String::from_utf8_lossy(date))
+        .into_iter()
+        .chain(digit_runs(&String::from_utf8_lossy(time)))
+        .filter_map(|run| run.parse().ok())
+        .collect();
+    match digits.as_slice() {
+        [y, mo, d, h, mi, s] => build_datetime(*y as i32, *mo, *d, *h, *mi, *s),
+        _ => None,
+    }
+}
+
+fn parse_list_info_icrd(data: &[u8]) -> Option<NaiveDateTime> {
+    if data.get(0..4)? != b"INFO" {
+        return None;
+    }
+    let mut pos = 4usize;
+    while pos + 8 <= data.len() {
+        let id = &data[pos..pos + 4];
+        let size = u32::from_le_bytes(data[pos + 4..pos + 8].try_into().ok()?) as usize;
+        let value = data.get(pos + 8..pos + 8 + size)?;
+        if id == b"ICRD" {
+            let text = String::from_utf8_lossy(value);
+            return parse_datetime_text(text.trim_end_matches('\0'));
+        }
+        pos += 8 + size + (size & 1);
+    }
+    None
+}
+
+// ===== ID3v2 =====
+
+fn read_id3_timestamp(path: &Path) -> Option<RecordingTimestamp> {
+    let mut file = File::open(path).ok()?;
+    let mut header = [0u8; 10];
+    file.read_exact(&mut header).ok()?;
+    if &header[0..3] != b"ID3" {
+        return None;
+    }
+    let major = header[3];
+    if major != 3 && major != 4 {
+        return None;
+    }
+    let tag_size = syncsafe(&header[6..10]) as usize;
+    let tag = read_exact_vec(&mut file, tag_size)?;
+
+    let mut pos = 0usize;
+    // Skip the extended header if present
+    if header[5] & 0x40 != 0 {
+        let ext_size = if major == 4 {
+            syncsafe(tag.get(0..4)?) as usize
+        } else {
+            u32::from_be_bytes(tag.get(0..4)?.try_into().ok()?) as usize + 4
+        };
+        pos += ext_size;
+    }
+
+    let mut tdrc = None;
+    let (mut tyer, mut tdat, mut time) = (None, None, None);
+
+    while pos + 10 <= tag.len() {
+        let id = &tag[pos..pos + 4];
+        if id[0] == 0 {
+            break; // padding
+        }
+        let size = if major == 4 {
+            syncsafe(&tag[pos + 4..pos + 8]) as usize
+        } else {
+            u32::from_be_bytes(tag[pos + 4..pos + 8].try_into().ok()?) as usize
+        };
+        let Some(body) = tag.get(pos + 10..pos + 10 + size) else {
+            break;
+        };
+        match id {
+            b"TDRC" => tdrc = decode_id3_text(body),
+            b"TYER" => tyer = decode_id3_text(body),
+            b"TDAT" => tdat = decode_id3_text(body),
+            b"TIME" => time = decode_id3_text(body),
+            _ => {}
+        }
+        pos += 10 + size;
+    }
+
+    let local = tdrc.as_deref().and_then(parse_datetime_text).or_else(|| {
+        // ID3v2.3 splits the date: TYER = YYYY, TDAT = DDMM, TIME = HHMM
+        let year: i32 = tyer?.trim().parse().ok()?;
+        let tdat = tdat?;
+        let time = time?;
+        let (day, month) = (tdat.get(0..2)?.parse().ok()?, tdat.get(2..4)?.parse().ok()?);
+        let (hour, minute) = (time.get(0..2)?.parse().ok()?, time.get(2..4)?.parse().ok()?);
+        build_datetime(year, month, day, hour, minute, 0)
+    })?;
+
+    Some(RecordingTimestamp {
+        time: RecordingTime::Local(local),
+        source: TimestampSource::Id3,
+    })
+}
+
+fn syncsafe(bytes: &[u8]) -> u32 {
+    bytes.iter().fold(0u32, |acc, b| (acc << 7) | (*b as u32 & 0x7f))
+}
+
+fn decode_id3_text(body: &[u8]) -> Option<String> {
+    let (encoding, text) = body.split_first()?;
+    let decoded = match encoding {
+        0 => text.iter().map(|&b| b as char).collect(),
+        1 | 2 => {
+            let mut big_endian = *encoding == 2;
+            let mut bytes = text;
+            if bytes.len() >= 2 {
+                match (bytes[0], bytes[1]) {
+                    (0xFE, 0xFF) => {
+                        big_endian = true;
+                        bytes = &bytes[2..];
+                    }
+                    (0xFF, 0xFE) => {
+                        big_endian = false;
+                        bytes = &bytes[2..];
+                    }
+                    _ => {}
+                }
+            }
+            let units: Vec<u16> = bytes
+                .chunks_exact(2)
+                .map(|c| {
+                    if big_endian {
+                        u16::from_be_bytes([c[0], c[1]])
+                    } else {
+                        u16::from_le_bytes([c[0], c[1]])
+                    }
+                })
+                .collect();
+            String::from_utf16_lossy(&units)
+        }
+        _ => String::from_utf8_lossy(text).to_string(),
+    };
+    let trimmed = decoded.trim_end_matches('\0').trim().to_string();
+    (!trimmed.is_empty()).then_some(trimmed)
+}
+
+// ===== MP4 =====
+
+/// Seconds between 1904-01-01 (MP4 epoch) and 1970-01-01
+const MP4_EPOCH_OFFSET: i64 = 2_082_844_800;
+
+fn read_mp4_timestamp(path: &Path) -> Option<RecordingTimestamp> {
+    let mut file = File::open(path).ok()?;
+    let file_len = file.metadata().ok()?.len();
+
+    let (moov_start, moov_end) = find_atom(&mut file, 0, file_len, b"moov")?;
+    let (mvhd_start, _) = find_atom(&mut file, moov_start, moov_end, b"mvhd")?;
+
+    file.seek(SeekFrom::Start(mvhd_start)).ok()?;
+    let mut version_flags = [0u8; 4];
+    file.read_exact(&mut version_flags).ok()?;
+    let creation_time = if version_flags[0] == 1 {
+        let mut buf = [0u8; 8];
+        file.read_exact(&mut buf).ok()?;
+        u64::from_be_bytes(buf) as i64
+    } else {
+        let mut buf = [0u8; 4];
+        file.read_exact(&mut buf).ok()?;
+        u32::from_be_bytes(buf) as i64
+    };
+
+    // Many devices leave creation_time at zero
+    if creation_time == 0 {
+        return None;
+    }
+    let utc = DateTime::from_timestamp(creation_time - MP4_EPOCH_OFFSET, 0)?;
+
+    Some(RecordingTimestamp {
+        time: RecordingTime::Absolute(utc),
+        source: TimestampSource::Mp4,
+    })
+}
+
+/// Find a child atom within [start, end), returning its payload range.
+fn find_atom(file: &mut File, start: u64, end: u64, name: &[u8; 4]) -> Option<(u64, u64)> {
+    let mut pos = start;
+    while pos + 8 <= end {
+        file.seek(SeekFrom::Start(pos)).ok()?;
+        let mut header = [0u8; 8];
+        file.read_exact(&mut header).ok()?;
+        let size32 = u32::from_be_bytes(header[0..4].try_into().ok()?) as u64;
+        let (size, header_len) = match size32 {
+            0 => (end - pos, 8),
+            1 => {
+                let mut large = [0u8; 8];
+                file.read_exact(&mut large).ok()?;
+                (u64::from_be_bytes(large), 16)
+            }
+            n => (n, 8),
+        };
+        if size < header_len {
+            return None;
+        }
+        // A corrupt size may overflow or run past the parent; stop there
+        let next = pos.checked_add(size).filter(|&next| next <= end)?;
+        if &header[4..8] == name {
+            return Some((pos + header_len, next));
+        }
+        pos = next;
+    }
+    None
+}
+
+// ===== File names =====
+
+/// Parse recorder naming patterns that embed the recording start time:
+/// `YYYYMMDD_HHMMSS`, `YYMMDD_HHMM(SS)`, `YYYYMMDDHHMMSS`, optionally with a
+/// text prefix (e.g. `REC_20230415_103000`). Counter-only names such as
+/// `ZOOM0001` or `DS400001` carry no time and return `None`.
+fn parse_file_name_timestamp(file_name: &str) -> Option<NaiveDateTime> {
+    let stem = Path::new(file_name).file_stem()?.to_str()?;
+    let runs = digit_runs(stem);
+
+    match runs.as_slice() {
+        [single, ..] if single.len() == 14 => parse_compact(&single[0..8], &single[8..14]),
+        [date, time, ..] if date.len() == 8 && time.len() == 6 => parse_compact(date, time),
+        [date, time, ..] if date.len() == 6 && (time.len() == 4 || time.len() == 6) => {
+            let year = 2000 + date[0..2].parse::<i32>().ok()?;
+            let month = date[2..4].parse().ok()?;
+            let day = date[4..6].parse().ok()?;
+            let hour = time[0..2].parse().ok()?;
+            let minute = time[2..4].parse().ok()?;
+            let second = time.get(4..6).map(|s| s.parse().ok()).unwrap_or(Some(0))?;
+            build_datetime(year, month, day, hour, minute, second)
+        }
+        _ => None,
+    }
+}
+
+fn parse_compact(date: &str, time: &str) -> Option<NaiveDateTime> {
+    build_datetime(
+        date[0..4].parse().ok()?,
+        date[4..6].parse().ok()?,
+        date[6..8].parse().ok()?,
+        time[0..2].parse().ok()?,
+        time[2..4].parse().ok()?,
+        time[4..6].parse().ok()?,
+    )
+}
+
+// ===== Shared helpers =====
+
+fn digit_runs(text: &str) -> Vec<&str> {
+    text.split(|c: char| !c.is_ascii_digit())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+/// Parse free-form "YYYY-MM-DD[ T]HH:MM[:SS]" text. Date-only values are rejected
+/// because they are less precise than the file name or mtime.
+fn parse_datetime_text(text: &str) -> Option<NaiveDateTime> {
+    let runs = digit_runs(text);
+    let nums: Vec<u32> = runs.iter().filter_map(|r| r.parse().ok()).collect();
+    match nums.as_slice() {
+        [y, mo, d, h, mi, s, ..] => build_datetime(*y as i32, *mo, *d, *h, *mi, *s),
+        [y, mo, d, h, mi] => build_datetime(*y as i32, *mo, *d, *h, *mi, 0),
+        _ => None,
+    }
+}
+
+fn build_datetime(year: i32, month: u32, day: u32, hour: u32, minute: u32, second: u32) -> Option<NaiveDateTime> {
+    // Recorders with a flat clock battery reset to their factory date; treat those as unknown
+    if year < 2000 {
+        return None;
+    }
+    NaiveDate::from_ymd_opt(year, month, day)?.and_hms_opt(hour, minute, second)
+}
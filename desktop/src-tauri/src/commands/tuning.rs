@@ -0,0 +1,194 @@
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use log::info;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use crate::config::MultipartConfig;
+
+const MIB: u64 = 1024 * 1024;
+/// Smallest part storage accepts, other than the last
+const MIN_PART_SIZE: u64 = 5 * MIB;
+/// Largest part storage accepts
+const MAX_PART_SIZE: u64 = 5 * 1024 * MIB;
+/// Most parts one multipart upload may have
+const MAX_PARTS: u64 = 10_000;
+/// Parts adaptive mode aims for, so bigger files get bigger parts
+const TARGET_PARTS: u64 = 100;
+/// Largest part adaptive mode picks unless the part limit needs more; a part
+/// retried on a slow link is sent again in full
+const MAX_ADAPTIVE_PART_SIZE: u64 = 32 * MIB;
+/// Change in throughput between windows that counts as better or worse
+const RATE_TOLERANCE: f64 = 0.1;
+
+/// Part size for a new multipart upload of `file_size` bytes. Adaptive mode
+/// grows it from the configured size towards `TARGET_PARTS` parts; either
+/// way it stays within storage's part size and part count limits.
+pub fn part_size(config: &MultipartConfig, file_size: u64) -> u64 {
+    let configured = config.part_size_mb.saturating_mul(MIB).max(MIN_PART_SIZE);
+    let size = if config.adaptive {
+        round_up_mib(file_size.div_ceil(TARGET_PARTS))
+            .min(MAX_ADAPTIVE_PART_SIZE)
+            .max(configured)
+    } else {
+        configured
+    };
+    size.max(round_up_mib(file_size.div_ceil(MAX_PARTS)))
+        .min(MAX_PART_SIZE)
+}
+
+fn round_up_mib(bytes: u64) -> u64 {
+    bytes.div_ceil(MIB) * MIB
+}
+
+/// Limits how many parts of an upload are in flight. In adaptive mode the
+/// limit is tuned once per window of finished parts: it keeps moving in the
+/// same direction while throughput improves, turns back when it drops, and
+/// halves when parts fail.
+pub struct PartConcurrency {
+    semaphore: Arc<Semaphore>,
+    adaptive: bool,
+    max: usize,
+    window: Mutex<Window>,
+}
+
+struct Window {
+    limit: usize,
+    /// Permits still held by parts that are retired when those parts finish,
+    /// after the limit was lowered below what was in flight
+    excess: usize,
+    started: Instant,
+    bytes: u64,
+    parts: usize,
+    /// Set once a failure in this window has lowered the limit
+    backed_off: bool,
+    /// Throughput of the previous window, in bytes per second
+    last_rate: Option<f64>,
+    /// Direction of the previous change: 1 up, -1 down, 0 held
+    last_step: i8,
+}
+
+/// Permission for one part to upload, returned when dropped
+pub struct PartSlot {
+    permit: Option<OwnedSemaphorePermit>,
+    owner: Arc<PartConcurrency>,
+}
+
+impl PartConcurrency {
+    pub fn new(config: &MultipartConfig) -> Arc<Self> {
+        let max = config.max_concurrency.max(1);
+        let limit = if config.adaptive {
+            config.concurrency.clamp(1, max)
+        } else {
+            config.concurrency.max(1)
+        };
+        Arc::new(Self {
+            semaphore: Arc::new(Semaphore::new(limit)),
+            adaptive: config.adaptive,
+            max,
+            window: Mutex::new(Window {
+                limit,
+                excess: 0,
+                started: Instant::now(),
+                bytes: 0,
+                parts: 0,
+                backed_off: false,
+                last_rate: None,
+                last_step: 0,
+            }),
+        })
+    }
+
+    pub async fn acquire(self: &Arc<Self>) -> PartSlot {
+        let permit = self.semaphore.clone().acquire_owned().await.unwrap();
+        PartSlot {
+            permit: Some(permit),
+            owner: self.clone(),
+        }
+    }
+
+    /// Count a part that was stored, closing the window once as many parts
+    /// as the limit allows have finished
+    pub fn record_part(&self, bytes: u64) {
+        let mut window = self.window.lock().unwrap();
+        window.bytes += bytes;
+        window.parts += 1;
+        if !self.adaptive || window.parts < window.limit.max(2) {
+            return;
+        }
+
+        let rate = window.bytes as f64 / window.started.elapsed().as_secs_f64().max(0.001);
+        let step: i8 = match window.last_rate {
+            None => 1,
+            Some(last) => {
+                let direction = if window.last_step < 0 { -1 } else { 1 };
+                if rate > last * (1.0 + RATE_TOLERANCE) {
+                    direction
+                } else if rate < last * (1.0 - RATE_TOLERANCE) {
+                    -direction
+                } else {
+                    0
+                }
+            }
+        };
+        let limit = window
+            .limit
+            .saturating_add_signed(step as isize)
+            .clamp(1, self.max);
+        window.last_rate = Some(rate);
+        window.last_step = step;
+        self.set_limit(&mut window, limit);
+        Self::reset(&mut window);
+    }
+
+    /// Count a failed or retried part; the first in a window halves the limit
+    pub fn record_error(&self) {
+        let mut window = self.window.lock().unwrap();
+        if !self.adaptive || window.backed_off {
+            return;
+        }
+        let limit = (window.limit / 2).max(1);
+        window.last_rate = None;
+        window.last_step = -1;
+        self.set_limit(&mut window, limit);
+        Self::reset(&mut window);
+        window.backed_off = true;
+    }
+
+    fn set_limit(&self, window: &mut Window, limit: usize) {
+        if limit == window.limit {
+            return;
+        }
+        info!("Multipart concurrency {} -> {}", window.limit, limit);
+        if limit > window.limit {
+            let grow = limit - window.limit;
+            let kept = grow.min(window.excess);
+            window.excess -= kept;
+            self.semaphore.add_permits(grow - kept);
+        } else {
+            let shrink = window.limit - limit;
+            let forgotten = self.semaphore.forget_permits(shrink);
+            window.excess += shrink - forgotten;
+        }
+        window.limit = limit;
+    }
+
+    fn reset(window: &mut Window) {
+        window.started = Instant::now();
+        window.bytes = 0;
+        window.parts = 0;
+        window.backed_off = false;
+    }
+}
+
+impl Drop for PartSlot {
+    fn drop(&mut self) {
+        let mut window = self.owner.window.lock().unwrap();
+        if window.excess > 0 {
+            window.excess -= 1;
+            if let Some(permit) = self.permit.take() {
+                permit.forget();
+            }
+        }
+    }
+}
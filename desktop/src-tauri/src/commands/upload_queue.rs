@@ -0,0 +1,326 @@
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use log::info;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, State};
+use tokio::sync::Notify;
+
+use crate::error::AppError;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "camelCase")]
+pub enum UploadPriority {
+    Low,
+    /// Files from recorder imports
+    #[default]
+    Normal,
+    /// Files someone picked by hand, which go ahead of import backlogs
+    High,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum QueueStatus {
+    Waiting,
+    /// Held back until resumed; other files go ahead of it
+    Paused,
+    Uploading,
+}
+
+/// A file waiting for or holding an upload slot, as listed to the frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueuedUpload {
+    pub id: u64,
+    pub batch_id: String,
+    /// Key of the file in the batch state
+    pub sha256: String,
+    pub file_name: String,
+    pub size_bytes: u64,
+    pub priority: UploadPriority,
+    pub status: QueueStatus,
+}
+
+struct Entry {
+    upload: QueuedUpload,
+    /// Stops this file's upload, whether it waits or runs
+    cancel: Arc<AtomicBool>,
+}
+
+/// The one upload scheduler shared by every batch. Each upload waits here
+/// for a slot; at most `concurrency` run at once. A free slot goes to the
+/// highest priority, then to the batch with the fewest uploads running, so
+/// batches take turns, and then to whichever file is earliest in the queue.
+pub struct UploadQueue {
+    app_handle: AppHandle,
+    state: Mutex<QueueState>,
+    changed: Notify,
+}
+
+struct QueueState {
+    entries: Vec<Entry>,
+    next_id: u64,
+    concurrency: usize,
+}
+
+pub type ManagedQueue = Arc<UploadQueue>;
+
+pub fn new_upload_queue(app_handle: AppHandle, concurrency: usize) -> ManagedQueue {
+    Arc::new(UploadQueue {
+        app_handle,
+        state: Mutex::new(QueueState {
+            entries: Vec::new(),
+            next_id: 1,
+            concurrency: concurrency.max(1),
+        }),
+        changed: Notify::new(),
+    })
+}
+
+/// A file to queue, described for the listing
+pub struct UploadJob<'a> {
+    pub batch_id: &'a str,
+    pub sha256: &'a str,
+    pub file_name: &'a str,
+    pub size_bytes: u64,
+    pub priority: UploadPriority,
+}
+
+/// Removes an entry from the queue once its upload ends or is dropped
+struct Ticket<'a> {
+    queue: &'a UploadQueue,
+    id: u64,
+}
+
+impl Drop for Ticket<'_> {
+    fn drop(&mut self) {
+        self.queue
+            .update(|state| state.entries.retain(|e| e.upload.id != self.id));
+    }
+}
+
+impl QueueState {
+    /// Entry to start next, if a slot is free
+    fn next_up(&self) -> Option<u64> {
+        let running = |batch_id: &str| {
+            self.entries
+                .iter()
+                .filter(|e| {
+                    e.upload.status == QueueStatus::Uploading && e.upload.batch_id == batch_id
+                })
+                .count()
+        };
+        let total_running = self
+            .entries
+            .iter()
+            .filter(|e| e.upload.status == QueueStatus::Uploading)
+            .count();
+        if total_running >= self.concurrency {
+            return None;
+        }
+        self.entries
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| e.upload.status == QueueStatus::Waiting)
+            .min_by_key(|(position, e)| {
+                (
+                    std::cmp::Reverse(e.upload.priority),
+                    running(&e.upload.batch_id),
+                    *position,
+                )
+            })
+            .map(|(_, e)| e.upload.id)
+    }
+
+    fn entry_mut(&mut self, id: u64) -> Result<&mut Entry, AppError> {
+        self.entries
+            .iter_mut()
+            .find(|e| e.upload.id == id)
+            .ok_or_else(|| AppError::NotFound(format!("No queued upload {}", id)))
+    }
+}
+
+impl UploadQueue {
+    /// Queue an upload and run it once it gets a slot. `upload` receives the
+    /// file's cancellation flag, which `cancel_upload` and a cancelled batch
+    /// set. Resolves to `Cancelled` if the file is cancelled while it waits.
+    pub async fn run<F, Fut>(
+        &self,
+        job: UploadJob<'_>,
+        batch_cancel: &AtomicBool,
+        upload: F,
+    ) -> Result<(), AppError>
+    where
+        F: FnOnce(Arc<AtomicBool>) -> Fut,
+        Fut: Future<Output = Result<(), AppError>>,
+    {
+        let cancel = Arc::new(AtomicBool::new(batch_cancel.load(Ordering::Relaxed)));
+        let id = self.update(|state| {
+            let id = state.next_id;
+            state.next_id += 1;
+            state.entries.push(Entry {
+                upload: QueuedUpload {
+                    id,
+                    batch_id: job.batch_id.to_string(),
+                    sha256: job.sha256.to_string(),
+                    file_name: job.file_name.to_string(),
+                    size_bytes: job.size_bytes,
+                    priority: job.priority,
+                    status: QueueStatus::Waiting,
+                },
+                cancel: cancel.clone(),
+            });
+            id
+        });
+        let _ticket = Ticket { queue: self, id };
+
+        loop {
+            let notified = self.changed.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+
+            if cancel.load(Ordering::Relaxed) || batch_cancel.load(Ordering::Relaxed) {
+                return Err(AppError::Cancelled);
+            }
+            if self.try_start(id) {
+                break;
+            }
+            notified.await;
+        }
+
+        upload(cancel).await
+    }
+
+    /// Take a free slot if it is this entry's turn. Only a start is announced:
+    /// waking the others after every check would have the waiting uploads
+    /// wake each other without end.
+    fn try_start(&self, id: u64) -> bool {
+        let snapshot = {
+            let mut state = self.state.lock().unwrap();
+            if state.next_up() != Some(id) {
+                return false;
+            }
+            if let Ok(entry) = state.entry_mut(id) {
+                entry.upload.status = QueueStatus::Uploading;
+            }
+            snapshot(&state)
+        };
+        self.announce(snapshot);
+        true
+    }
+
+    /// Change the queue, then wake waiting uploads and tell the frontend
+    fn update<T>(&self, change: impl FnOnce(&mut QueueState) -> T) -> T {
+        let (result, snapshot) = {
+            let mut state = self.state.lock().unwrap();
+            let result = change(&mut state);
+            (result, snapshot(&state))
+        };
+        self.announce(snapshot);
+        result
+    }
+
+    fn announce(&self, snapshot: Vec<QueuedUpload>) {
+        self.changed.notify_waiters();
+        let _ = self.app_handle.emit("upload-queue", snapshot);
+    }
+
+    pub fn set_concurrency(&self, concurrency: usize) {
+        self.update(|state| state.concurrency = concurrency.max(1));
+    }
+
+    /// Stop every upload of a batch, queued or running
+    pub fn cancel_batch(&self, batch_id: &str) {
+        self.update(|state| {
+            for entry in state
+                .entries
+                .iter()
+                .filter(|e| e.upload.batch_id == batch_id)
+            {
+                entry.cancel.store(true, Ordering::Relaxed);
+            }
+        });
+    }
+}
+
+fn snapshot(state: &QueueState) -> Vec<QueuedUpload> {
+    state.entries.iter().map(|e| e.upload.clone()).collect()
+}
+
+#[tauri::command]
+pub fn get_upload_queue(queue: State<'_, ManagedQueue>) -> Vec<QueuedUpload> {
+    snapshot(&queue.state.lock().unwrap())
+}
+
+#[tauri::command]
+pub fn set_upload_priority(
+    id: u64,
+    priority: UploadPriority,
+    queue: State<'_, ManagedQueue>,
+) -> Result<(), AppError> {
+    queue.update(|state| {
+        state.entry_mut(id)?.upload.priority = priority;
+        Ok(())
+    })
+}
+
+/// Move a file to `position` in the queue, counted from the front
+#[tauri::command]
+pub fn move_upload(
+    id: u64,
+    position: usize,
+    queue: State<'_, ManagedQueue>,
+) -> Result<(), AppError> {
+    queue.update(|state| {
+        let from = state
+            .entries
+            .iter()
+            .position(|e| e.upload.id == id)
+            .ok_or_else(|| AppError::NotFound(format!("No queued upload {}", id)))?;
+        let entry = state.entries.remove(from);
+        let to = position.min(state.entries.len());
+        state.entries.insert(to, entry);
+        Ok(())
+    })
+}
+
+/// Hold a waiting file back, or let it go again. Uploads already running
+/// are not interrupted; cancel them instead.
+#[tauri::command]
+pub fn set_upload_paused(
+    id: u64,
+    paused: bool,
+    queue: State<'_, ManagedQueue>,
+) -> Result<(), AppError> {
+    queue.update(|state| {
+        let entry = state.entry_mut(id)?;
+        entry.upload.status = match (entry.upload.status, paused) {
+            (QueueStatus::Uploading, _) => {
+                return Err(AppError::InvalidInput(format!(
+                    "{} is already uploading",
+                    entry.upload.file_name
+                )))
+            }
+            (_, true) => QueueStatus::Paused,
+            (_, false) => QueueStatus::Waiting,
+        };
+        Ok(())
+    })
+}
+
+/// Take a file out of the queue, stopping its upload if it has started. The
+/// file is left unuploaded in its batch.
+#[tauri::command]
+pub fn cancel_upload(id: u64, queue: State<'_, ManagedQueue>) -> Result<(), AppError> {
+    queue.update(|state| {
+        let entry = state.entry_mut(id)?;
+        entry.cancel.store(true, Ordering::Relaxed);
+        info!(
+            "Cancellation requested for upload of {}",
+            entry.upload.file_name
+        );
+        Ok(())
+    })
+}
@@ -1,80 +1,312 @@
-use log::info;
-use reqwest::Client;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use chrono::Utc;
+use futures::StreamExt;
+use log::{info, warn};
+use reqwest::header::CONTENT_LENGTH;
+use reqwest::Body;
 use tauri::{AppHandle, Emitter};
 use tokio::fs;
 use tokio::io::AsyncReadExt;
-use tokio::sync::Semaphore;
 
-use crate::commands::api_client::{AuthHeaders, CompletedPart, PresignResult};
+use crate::commands::api_client::{
+    self, ChunkRegistration, CompletedPart, PresignResult, RecordingRecord, UploadedPart,
+};
+use crate::commands::bandwidth;
+use crate::commands::hasher::{self, hash_file};
+use crate::commands::http::{self, Activity, ApiClient};
+use crate::commands::retry::{self, RetryAttempt};
+use crate::commands::splitter::ChunkInfo;
+use crate::commands::tuning::{self, PartConcurrency};
+use crate::config::{MultipartConfig, RetryConfig};
 use crate::error::AppError;
 use crate::events::UploadProgress;
 use crate::state::{persist_state, ManagedStateInner};
 
-pub const MULTIPART_THRESHOLD: u64 = 100 * 1024 * 1024; // 100MB
-pub const PART_SIZE: u64 = 10 * 1024 * 1024; // 10MB
-pub const MAX_CONCURRENT: usize = 4;
-
-/// Upload a single file via presigned PUT URL
+/// Part size of multipart uploads saved before the part size was recorded
+pub const LEGACY_PART_SIZE: u64 = 10 * 1024 * 1024; // 10MB
+/// Bytes read from disk per write of a streamed upload body
+const STREAM_BUFFER: usize = 256 * 1024;
+/// Minimum time between progress events of a streamed upload
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+/// How often a part in flight checks whether its batch was cancelled
+const CANCEL_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Upload a single file via presigned PUT URL, streaming it from disk.
+/// Progress is reported as the body is written; setting `cancel` aborts the
+/// request mid-stream, and so does the body stalling.
 pub async fn upload_single(
     file_path: &str,
     presign_result: &PresignResult,
+    api: &ApiClient,
     app_handle: &AppHandle,
     file_name: &str,
     recording_id: &str,
+    cancel: &Arc<AtomicBool>,
 ) -> Result<(), AppError> {
-    let data = fs::read(file_path).await?;
-    let total_bytes = data.len() as u64;
+    let file = fs::File::open(file_path).await?;
+    let total_bytes = file.metadata().await?.len();
 
-    let client = Client::new();
-    let mut request = client.put(&presign_result.url);
+    let mut request = api.storage().put(&presign_result.url);
 
     for (key, value) in &presign_result.headers {
         request = request.header(key.as_str(), value.as_str());
     }
 
-    let _ = app_handle.emit(
-        "upload-progress",
-        UploadProgress {
-            recording_id: recording_id.to_string(),
-            file_name: file_name.to_string(),
-            bytes_uploaded: 0,
-            total_bytes,
-            part_number: None,
-            total_parts: None,
-        },
-    );
-
-    let res = request.body(data).send().await?;
+    let emit_progress = {
+        let app_handle = app_handle.clone();
+        let recording_id = recording_id.to_string();
+        let file_name = file_name.to_string();
+        move |bytes_uploaded: u64| {
+            let _ = app_handle.emit(
+                "upload-progress",
+                UploadProgress {
+                    recording_id: recording_id.clone(),
+                    file_name: file_name.clone(),
+                    bytes_uploaded,
+                    total_bytes,
+                    ..Default::default()
+                },
+            );
+        }
+    };
+    emit_progress(0);
+
+    let activity = Activity::default();
+    let body = BodyStream {
+        file,
+        bytes_sent: 0,
+        last_report: Instant::now(),
+        cancel: cancel.clone(),
+        activity: activity.clone(),
+        on_progress: emit_progress.clone(),
+    };
+    let stream = futures::stream::try_unfold(body, |mut body| async move {
+        body.activity.touch();
+        if body.cancel.load(Ordering::Relaxed) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::Interrupted,
+                "upload cancelled",
+            ));
+        }
+        let mut buf = vec![0u8; STREAM_BUFFER];
+        let n = body.file.read(&mut buf).await?;
+        if n == 0 {
+            return Ok(None);
+        }
+        buf.truncate(n);
+        bandwidth::throttle(n).await;
+        body.activity.touch();
+        body.bytes_sent += n as u64;
+        if body.last_report.elapsed() >= PROGRESS_INTERVAL {
+            body.last_report = Instant::now();
+            (body.on_progress)(body.bytes_sent);
+        }
+        Ok(Some((buf, body)))
+    });
+
+    // Presigned PUTs need the length up front; a streamed body would
+    // otherwise go out chunked, which object storage rejects
+    let send = async {
+        request
+            .header(CONTENT_LENGTH, total_bytes)
+            .body(Body::wrap_stream(stream))
+            .send()
+            .await
+            .map_err(|e| match cancel.load(Ordering::Relaxed) {
+                true => AppError::Cancelled,
+                false => AppError::Http(e),
+            })
+    };
+    let res = http::watch_stall(&activity, api.stall_timeout(), send).await?;
 
     if !res.status().is_success() {
         let status = res.status().as_u16();
+        let retry_after = retry::retry_after(res.headers());
         let text = res.text().await.unwrap_or_default();
         return Err(AppError::Api {
             status,
             message: format!("Upload failed: {}", text),
+            retry_after,
         });
     }
 
-    let _ = app_handle.emit(
-        "upload-progress",
-        UploadProgress {
-            recording_id: recording_id.to_string(),
-            file_name: file_name.to_string(),
-            bytes_uploaded: total_bytes,
-            total_bytes,
-            part_number: None,
-            total_parts: None,
-        },
-    );
+    emit_progress(total_bytes);
 
     Ok(())
 }
 
-/// Upload a file via multipart upload with concurrent parts
+/// State carried between reads of a streamed upload body
+struct BodyStream<F> {
+    file: fs::File,
+    bytes_sent: u64,
+    last_report: Instant,
+    cancel: Arc<AtomicBool>,
+    activity: Activity,
+    on_progress: F,
+}
+
+/// Progress entry announcing that a step of a file's upload is being retried
+pub fn retry_progress(recording_id: &str, file_name: &str, retry: &RetryAttempt) -> UploadProgress {
+    UploadProgress {
+        recording_id: recording_id.to_string(),
+        file_name: file_name.to_string(),
+        attempt: Some(retry.attempt),
+        retry_delay_ms: Some(retry.delay.as_millis() as u64),
+        error: Some(retry.error.to_string()),
+        ..Default::default()
+    }
+}
+
+/// Retry callback that emits a `retry_progress` entry
+pub fn report_retry<'a>(
+    app_handle: &'a AppHandle,
+    recording_id: &'a str,
+    file_name: &'a str,
+) -> impl FnMut(&RetryAttempt) + 'a {
+    move |retry| {
+        let _ = app_handle.emit(
+            "upload-progress",
+            retry_progress(recording_id, file_name, retry),
+        );
+    }
+}
+
+/// Complete a raw upload and check the server's record of it against the file
+/// sent: storage must hold `size_bytes` bytes with the checksum `sha256` (the
+/// file's SHA-256, or the composite of its parts' for a multipart upload), and
+/// the recording must have moved past uploading. An object that never arrived
+/// fails with `AppError::NotReceived` and any other difference with
+/// `AppError::Mismatch`, for the caller to upload the file again.
+pub async fn verify_upload(
+    api: &ApiClient,
+    recording_id: &str,
+    size_bytes: u64,
+    sha256: &str,
+    app_handle: &AppHandle,
+    file_name: &str,
+    retry: &RetryConfig,
+) -> Result<(), AppError> {
+    let report = || report_retry(app_handle, recording_id, file_name);
+    retry::with_retry(retry, "Completion", report(), || {
+        api_client::complete_upload(api, recording_id.to_string())
+    })
+    .await?;
+    let record = retry::with_retry(retry, "Verification", report(), || {
+        api_client::get_recording(api, recording_id.to_string())
+    })
+    .await?;
+
+    check_record(&record, size_bytes, sha256)?;
+    info!("Verified upload of {}", file_name);
+    Ok(())
+}
+
+fn check_record(record: &RecordingRecord, size_bytes: u64, sha256: &str) -> Result<(), AppError> {
+    match record.stored_size_bytes {
+        Some(stored) if stored == size_bytes => {}
+        Some(stored) => {
+            return Err(AppError::Mismatch(format!(
+                "storage holds {} bytes, {} were sent",
+                stored, size_bytes
+            )))
+        }
+        None => return Err(AppError::NotReceived("storage holds no file".into())),
+    }
+    match record.stored_sha256.as_deref() {
+        Some(stored) if stored.eq_ignore_ascii_case(sha256) => {}
+        stored => {
+            return Err(AppError::Mismatch(format!(
+                "stored SHA-256 {} differs from the file's {}",
+                stored.unwrap_or("(none)"),
+                sha256
+            )))
+        }
+    }
+    if matches!(record.status.as_str(), "REGISTERED" | "UPLOADING") {
+        return Err(AppError::Mismatch(format!(
+            "server still reports the recording as {}",
+            record.status
+        )));
+    }
+    Ok(())
+}
+
+/// Register each transcription chunk with the server and upload it via the
+/// presigned PUT URL it returns. Re-registering a chunk replaces it, so a
+/// failed batch can simply be retried.
+pub async fn upload_chunks(
+    chunks: &[ChunkInfo],
+    api: &ApiClient,
+    recording_id: &str,
+    app_handle: &AppHandle,
+    retry: &RetryConfig,
+    cancel: &Arc<AtomicBool>,
+) -> Result<(), AppError> {
+    for chunk in chunks {
+        let file_name = std::path::Path::new(&chunk.path)
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        let sha256 = hash_file(chunk.path.clone(), app_handle.clone()).await?;
+
+        let presign_result = retry::with_retry(
+            retry,
+            "Chunk registration",
+            report_retry(app_handle, recording_id, &file_name),
+            || {
+                api_client::register_chunk(
+                    api,
+                    recording_id.to_string(),
+                    ChunkRegistration {
+                        chunk_index: chunk.index,
+                        start_ms: chunk.start_ms,
+                        end_ms: chunk.end_ms,
+                        file_name: file_name.clone(),
+                        sha256: sha256.clone(),
+                    },
+                )
+            },
+        )
+        .await?;
+
+        info!("Uploading chunk {} ({}-{} ms)", file_name, chunk.start_ms, chunk.end_ms);
+        retry::with_retry(
+            retry,
+            "Chunk upload",
+            report_retry(app_handle, recording_id, &file_name),
+            || {
+                upload_single(
+                    &chunk.path,
+                    &presign_result,
+                    api,
+                    app_handle,
+                    &file_name,
+                    recording_id,
+                    cancel,
+                )
+            },
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Upload a file via multipart upload with concurrent parts. A multipart
+/// upload saved in the batch state is resumed: parts the server already holds
+/// are skipped, and their ETags come from its listing. A resumed upload keeps
+/// the part size it was started with. On cancellation or a failure that
+/// retrying cannot fix, the upload is aborted on the server.
+///
+/// Returns the composite checksum of the parts, which the server records for
+/// the assembled object in place of its SHA-256.
 pub async fn upload_multipart(
     file_path: &str,
-    server_url: &str,
-    auth: &AuthHeaders,
+    api: &ApiClient,
     recording_id: &str,
     upload_id: &str,
     batch_id: &str,
@@ -82,165 +314,399 @@ pub async fn upload_multipart(
     managed_state: &ManagedStateInner,
     app_handle: &AppHandle,
     file_name: &str,
-) -> Result<(), AppError> {
+    retry: &RetryConfig,
+    multipart: &MultipartConfig,
+    cancel: &Arc<AtomicBool>,
+) -> Result<String, AppError> {
     let metadata = fs::metadata(file_path).await?;
     let file_size = metadata.len();
-    let total_parts = ((file_size + PART_SIZE - 1) / PART_SIZE) as u32;
 
-    // Load previously completed parts from state for resume
-    let completed_parts: std::collections::HashSet<u32> = {
+    // Multipart upload and parts saved by an earlier attempt
+    let saved = {
         let state = managed_state.inner.lock().unwrap();
         state
             .batches
             .get(batch_id)
             .and_then(|b| b.files.get(sha256))
-            .and_then(|f| f.completed_parts.as_ref())
-            .map(|parts| parts.iter().copied().collect())
-            .unwrap_or_default()
+            .and_then(|f| {
+                Some((
+                    f.multipart_upload_id.clone()?,
+                    f.completed_parts.clone().unwrap_or_default(),
+                    f.multipart_part_size.unwrap_or(LEGACY_PART_SIZE),
+                ))
+            })
+    };
+
+    let report = || report_retry(app_handle, recording_id, file_name);
+    let resumed = match saved {
+        Some((mp_upload_id, saved_parts, part_size)) => {
+            let listed = retry::with_retry(retry, "Part listing", report(), || {
+                api_client::list_parts(
+                    api,
+                    recording_id.to_string(),
+                    mp_upload_id.clone(),
+                )
+            })
+            .await;
+            match listed {
+                Ok(listed) => {
+                    let parts = reconcile_parts(listed, &saved_parts, file_size, part_size);
+                    info!(
+                        "Resuming multipart upload of {}: {}/{} parts stored",
+                        file_name,
+                        parts.len(),
+                        file_size.div_ceil(part_size)
+                    );
+                    Some((mp_upload_id, parts, part_size))
+                }
+                Err(AppError::Api { status: 404, .. }) => {
+                    info!(
+                        "Multipart upload of {} no longer exists, starting over",
+                        file_name
+                    );
+                    None
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        None => None,
     };
 
-    // Save multipart upload ID to state for resume
+    let (mp_upload_id, mut stored_parts, part_size) = match resumed {
+        Some(resumed) => resumed,
+        None => {
+            let presign_result = retry::with_retry(retry, "Presign", report(), || {
+                api_client::presign(
+                    api,
+                    recording_id.to_string(),
+                    upload_id.to_string(),
+                    Some(true),
+                    None,
+                )
+            })
+            .await?;
+            let mp_upload_id = presign_result
+                .upload_id
+                .unwrap_or_else(|| upload_id.to_string());
+            (mp_upload_id, Vec::new(), tuning::part_size(multipart, file_size))
+        }
+    };
+    let total_parts = file_size.div_ceil(part_size) as u32;
+
+    // Save the multipart upload and the parts it holds for resume
     {
         let mut state = managed_state.inner.lock().unwrap();
         if let Some(batch) = state.batches.get_mut(batch_id) {
             if let Some(file_status) = batch.files.get_mut(sha256) {
-                if file_status.multipart_upload_id.is_none() {
-                    file_status.multipart_upload_id = Some(upload_id.to_string());
+                if file_status.multipart_upload_id.as_ref() != Some(&mp_upload_id) {
+                    file_status.multipart_started_at = Some(Utc::now().to_rfc3339());
                 }
+                file_status.multipart_upload_id = Some(mp_upload_id.clone());
+                file_status.multipart_part_size = Some(part_size);
+                file_status.completed_parts = Some(stored_parts.clone());
             }
         }
     }
     persist_state(managed_state).await?;
 
-    // Determine pending parts
-    let pending_parts: Vec<u32> = (1..=total_parts)
-        .filter(|p| !completed_parts.contains(p))
-        .collect();
+    let outcome = async {
+        // Determine pending parts
+        let pending_parts: Vec<u32> = (1..=total_parts)
+            .filter(|p| !stored_parts.iter().any(|s| s.part_number == *p))
+            .collect();
+
+        let concurrency = PartConcurrency::new(multipart);
+
+        let mut handles = Vec::new();
+
+        for part_number in pending_parts {
+            let concurrency = concurrency.clone();
+            let api = api.clone();
+            let recording_id = recording_id.to_string();
+            let upload_id = mp_upload_id.clone();
+            let file_path = file_path.to_string();
+            let app_handle = app_handle.clone();
+            let file_name = file_name.to_string();
+            let retry = retry.clone();
+            let cancel = cancel.clone();
+
+            let handle = tokio::spawn(async move {
+                let _slot = concurrency.acquire().await;
+                if cancel.load(Ordering::Relaxed) {
+                    return Err(AppError::Cancelled);
+                }
 
-    let all_parts = std::sync::Arc::new(tokio::sync::Mutex::new(Vec::<CompletedPart>::new()));
-    let semaphore = std::sync::Arc::new(Semaphore::new(MAX_CONCURRENT));
+                // Read the part from file
+                let start = (part_number as u64 - 1) * part_size;
+                let end = std::cmp::min(start + part_size, file_size);
+                let part_len = (end - start) as usize;
+
+                let mut file = tokio::fs::File::open(&file_path).await?;
+                tokio::io::AsyncSeekExt::seek(&mut file, std::io::SeekFrom::Start(start)).await?;
+                let mut buf = vec![0u8; part_len];
+                file.read_exact(&mut buf).await?;
+
+                // Sign each attempt's URL with the checksum of the bytes read
+                let checksum = hasher::sha256_base64(&buf);
+                let on_retry = |attempt: &RetryAttempt| {
+                    concurrency.record_error();
+                    let _ = app_handle.emit(
+                        "upload-progress",
+                        UploadProgress {
+                            part_number: Some(part_number),
+                            total_parts: Some(total_parts),
+                            ..retry_progress(&recording_id, &file_name, attempt)
+                        },
+                    );
+                };
+                let what = format!("Part {}", part_number);
+                let attempts = retry::with_retry(&retry, &what, on_retry, || {
+                    upload_part(
+                        &api,
+                        &recording_id,
+                        &upload_id,
+                        part_number,
+                        &buf,
+                        &checksum,
+                    )
+                });
+                let etag = tokio::select! {
+                    etag = attempts => etag,
+                    _ = cancelled(&cancel) => return Err(AppError::Cancelled),
+                };
+                let etag = match etag {
+                    Ok(etag) => etag,
+                    Err(e) => {
+                        concurrency.record_error();
+                        return Err(e);
+                    }
+                };
+                concurrency.record_part(part_len as u64);
+
+                let _ = app_handle.emit(
+                    "upload-progress",
+                    UploadProgress {
+                        recording_id: recording_id.to_string(),
+                        file_name: file_name.to_string(),
+                        bytes_uploaded: end,
+                        total_bytes: file_size,
+                        part_number: Some(part_number),
+                        total_parts: Some(total_parts),
+                        ..Default::default()
+                    },
+                );
+
+                Ok::<CompletedPart, AppError>(CompletedPart {
+                    part_number,
+                    etag,
+                    checksum_sha256: Some(checksum),
+                })
+            });
 
-    let mut handles = Vec::new();
+            handles.push(handle);
+        }
 
-    for part_number in pending_parts {
-        let sem = semaphore.clone();
-        let all_parts = all_parts.clone();
-        let server_url = server_url.to_string();
-        let auth = auth.clone();
-        let recording_id = recording_id.to_string();
-        let upload_id = upload_id.to_string();
-        let file_path = file_path.to_string();
-        let app_handle = app_handle.clone();
-        let file_name = file_name.to_string();
+        // Wait for all uploads, saving each part with its ETag as it lands
+        let mut handles = handles.into_iter();
+        while let Some(handle) = handles.next() {
+            let part = match handle.await.map_err(|e| AppError::Other(e.to_string())) {
+                Ok(Ok(part)) => part,
+                Ok(Err(e)) | Err(e) => {
+                    // Stop the other parts before the upload is aborted or kept to resume
+                    handles.for_each(|h| h.abort());
+                    return Err(e);
+                }
+            };
+
+            {
+                let mut state = managed_state.inner.lock().unwrap();
+                if let Some(batch) = state.batches.get_mut(batch_id) {
+                    if let Some(file_status) = batch.files.get_mut(sha256) {
+                        let parts = file_status.completed_parts.get_or_insert_with(Vec::new);
+                        parts.retain(|p| p.part_number != part.part_number);
+                        parts.push(part.clone());
+                    }
+                }
+            }
+            persist_state(managed_state).await?;
+            stored_parts.push(part);
+        }
 
-        let handle = tokio::spawn(async move {
-            let _permit = sem.acquire().await.unwrap();
-
-            // Read the part from file
-            let start = (part_number as u64 - 1) * PART_SIZE;
-            let end = std::cmp::min(start + PART_SIZE, file_size);
-            let part_len = (end - start) as usize;
-
-            let mut file = tokio::fs::File::open(&file_path).await?;
-            tokio::io::AsyncSeekExt::seek(&mut file, std::io::SeekFrom::Start(start)).await?;
-            let mut buf = vec![0u8; part_len];
-            file.read_exact(&mut buf).await?;
-
-            // Get presigned URL for this part
-            let presigned_url = crate::commands::api_client::presign_part(
-                server_url,
-                auth,
-                recording_id.clone(),
-                upload_id,
-                part_number,
+        // Complete multipart upload
+        stored_parts.sort_by_key(|p| p.part_number);
+        let checksums: Option<Vec<&str>> = stored_parts
+            .iter()
+            .map(|p| p.checksum_sha256.as_deref())
+            .collect();
+        let composite = checksums
+            .and_then(|checksums| hasher::multipart_sha256(&checksums))
+            .ok_or_else(|| {
+                AppError::Other("Multipart upload has a part without a valid checksum".into())
+            })?;
+
+        info!(
+            "Completing multipart upload for {} with {} parts",
+            recording_id,
+            stored_parts.len()
+        );
+
+        retry::with_retry(retry, "Multipart completion", report(), || {
+            api_client::complete_multipart(
+                api,
+                recording_id.to_string(),
+                mp_upload_id.clone(),
+                stored_parts.clone(),
             )
-            .await?;
+        })
+        .await?;
 
-            // Upload part
-            let client = Client::new();
-            let res = client.put(&presigned_url).body(buf).send().await?;
-
-            if !res.status().is_success() {
-                let status = res.status().as_u16();
-                let text = res.text().await.unwrap_or_default();
-                return Err(AppError::Api {
-                    status,
-                    message: format!("Part {} upload failed: {}", part_number, text),
-                });
+        Ok(composite)
+    }
+    .await;
+
+    match &outcome {
+        Ok(_) => clear_multipart(managed_state, batch_id, sha256),
+        // Retries ran out on what may be a passing outage; keep it to resume
+        Err(e) if retry::is_retryable(e) => {}
+        Err(e) => {
+            info!("Aborting multipart upload of {}: {}", file_name, e);
+            match api_client::abort_multipart(
+                api,
+                recording_id.to_string(),
+                mp_upload_id.clone(),
+            )
+            .await
+            {
+                Ok(()) => clear_multipart(managed_state, batch_id, sha256),
+                Err(abort_err) => warn!(
+                    "Could not abort multipart upload of {}, leaving it to the sweep: {}",
+                    file_name, abort_err
+                ),
             }
+        }
+    }
+    persist_state(managed_state).await?;
 
-            let etag = res
-                .headers()
-                .get("etag")
-                .and_then(|v| v.to_str().ok())
-                .map(|s| s.to_string())
-                .ok_or_else(|| {
-                    AppError::Other(format!(
-                        "Part {} upload succeeded but response missing ETag header",
-                        part_number
-                    ))
-                })?;
-
-            let _ = app_handle.emit(
-                "upload-progress",
-                UploadProgress {
-                    recording_id: recording_id.to_string(),
-                    file_name: file_name.to_string(),
-                    bytes_uploaded: end,
-                    total_bytes: file_size,
-                    part_number: Some(part_number),
-                    total_parts: Some(total_parts),
-                },
-            );
-
-            all_parts.lock().await.push(CompletedPart {
-                part_number,
-                etag,
-            });
-
-            Ok::<u32, AppError>(part_number)
-        });
+    outcome
+}
 
-        handles.push(handle);
+/// Forget a file's multipart upload once it has been completed or aborted
+pub fn clear_multipart(managed_state: &ManagedStateInner, batch_id: &str, sha256: &str) {
+    let mut state = managed_state.inner.lock().unwrap();
+    if let Some(file_status) = state
+        .batches
+        .get_mut(batch_id)
+        .and_then(|b| b.files.get_mut(sha256))
+    {
+        file_status.multipart_upload_id = None;
+        file_status.multipart_started_at = None;
+        file_status.multipart_part_size = None;
+        file_status.completed_parts = None;
     }
+}
 
-    // Wait for all uploads and persist progress
-    for handle in handles {
-        let part_number = handle.await.map_err(|e| AppError::Other(e.to_string()))??;
-
-        // Update completed parts in state
-        {
-            let mut state = managed_state.inner.lock().unwrap();
-            if let Some(batch) = state.batches.get_mut(batch_id) {
-                if let Some(file_status) = batch.files.get_mut(sha256) {
-                    let parts = file_status.completed_parts.get_or_insert_with(Vec::new);
-                    if !parts.contains(&part_number) {
-                        parts.push(part_number);
-                    }
-                }
-            }
-        }
-        persist_state(managed_state).await?;
+/// Resolves once `cancel` is set, to race a request already in flight
+async fn cancelled(cancel: &AtomicBool) {
+    while !cancel.load(Ordering::Relaxed) {
+        tokio::time::sleep(CANCEL_POLL_INTERVAL).await;
     }
+}
 
-    // Complete multipart upload
-    let mut sorted_parts = all_parts.lock().await.clone();
-    sorted_parts.sort_by_key(|p| p.part_number);
-
-    info!(
-        "Completing multipart upload for {} with {} parts",
-        recording_id,
-        sorted_parts.len()
-    );
+/// Parts of a resumed upload that can be kept: those the server holds at the
+/// size this file's part would have, with a checksum to verify the upload by.
+/// The server's ETag wins; the checksum comes from its listing or else from
+/// what was saved when the part went up. Parts with neither are sent again.
+fn reconcile_parts(
+    listed: Vec<UploadedPart>,
+    saved: &[CompletedPart],
+    file_size: u64,
+    part_size: u64,
+) -> Vec<CompletedPart> {
+    listed
+        .into_iter()
+        .filter(|part| {
+            let start = (part.part_number as u64).saturating_sub(1) * part_size;
+            part.part_number >= 1
+                && start < file_size
+                && part.size == part_size.min(file_size - start)
+        })
+        .filter_map(|part| {
+            let saved_checksum = saved
+                .iter()
+                .find(|s| s.part_number == part.part_number)
+                .and_then(|s| s.checksum_sha256.clone());
+            Some(CompletedPart {
+                part_number: part.part_number,
+                etag: part.etag,
+                checksum_sha256: Some(part.checksum_sha256.or(saved_checksum)?),
+            })
+        })
+        .collect()
+}
 
-    crate::commands::api_client::complete_multipart(
-        server_url.to_string(),
-        auth.clone(),
+/// Presign one part of a multipart upload and PUT it, returning its ETag
+async fn upload_part(
+    api: &ApiClient,
+    recording_id: &str,
+    upload_id: &str,
+    part_number: u32,
+    body: &[u8],
+    checksum: &str,
+) -> Result<String, AppError> {
+    let presigned = api_client::presign_part(
+        api,
         recording_id.to_string(),
         upload_id.to_string(),
-        sorted_parts,
+        part_number,
+        Some(checksum.to_string()),
     )
     .await?;
 
-    Ok(())
+    let mut request = api.storage().put(&presigned.url);
+    for (key, value) in &presigned.headers {
+        request = request.header(key.as_str(), value.as_str());
+    }
+    // Sent in pieces so the bandwidth cap can pace it
+    let activity = Activity::default();
+    let pieces: Vec<Vec<u8>> = body.chunks(STREAM_BUFFER).map(<[u8]>::to_vec).collect();
+    let progress = activity.clone();
+    let stream = futures::stream::iter(pieces).then(move |piece| {
+        let activity = progress.clone();
+        async move {
+            activity.touch();
+            bandwidth::throttle(piece.len()).await;
+            activity.touch();
+            Ok::<_, std::io::Error>(piece)
+        }
+    });
+    let send = async {
+        Ok(request
+            .header(CONTENT_LENGTH, body.len())
+            .body(Body::wrap_stream(stream))
+            .send()
+            .await?)
+    };
+    let res = http::watch_stall(&activity, api.stall_timeout(), send).await?;
+
+    if !res.status().is_success() {
+        let status = res.status().as_u16();
+        let retry_after = retry::retry_after(res.headers());
+        let text = res.text().await.unwrap_or_default();
+        return Err(AppError::Api {
+            status,
+            message: format!("Part {} upload failed: {}", part_number, text),
+            retry_after,
+        });
+    }
+
+    res.headers()
+        .get("etag")
+        .and_then(|v| v.to_str().ok())
+        .map(|s| s.to_string())
+        .ok_or_else(|| {
+            AppError::Other(format!(
+                "Part {} upload succeeded but response missing ETag header",
+                part_number
+            ))
+        })
 }
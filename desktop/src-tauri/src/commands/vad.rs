@@ -0,0 +1,153 @@
+use serde::{Deserialize, Serialize};
+
+use crate::commands::pcm::{self, MonoSink};
+use crate::config::ScreeningConfig;
+use crate::error::AppError;
+
+/// Length of the frames whose energy is classified as speech or not
+const FRAME_MS: u64 = 30;
+/// Speech has to stand this far above the running noise floor
+const NOISE_MARGIN_DB: f64 = 10.0;
+/// How fast the noise floor estimate rises while the level stays above it
+const FLOOR_RISE_DB_PER_FRAME: f64 = 0.05;
+/// Pauses shorter than this do not end a speech segment
+const HANGOVER_MS: u64 = 300;
+/// Segments shorter than this are clicks and bumps, not speech
+const MIN_SEGMENT_MS: u64 = 200;
+
+/// How much of a recording contains speech, from an energy-based detector
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct VoiceActivity {
+    pub duration_ms: u64,
+    pub speech_ms: u64,
+    /// `speech_ms / duration_ms`
+    pub speech_ratio: f64,
+    pub longest_speech_ms: u64,
+    /// Below the configured speech ratio or segment length
+    pub likely_empty: bool,
+}
+
+/// Measure the speech content of a recording
+pub async fn analyze_voice_activity(
+    path: &str,
+    config: &ScreeningConfig,
+    ffmpeg_path: Option<&str>,
+) -> Result<VoiceActivity, AppError> {
+    let threshold_db = config.speech_threshold_db;
+    let detector = pcm::decode_mono(path, ffmpeg_path, move |rate| {
+        Detector::new(rate, threshold_db)
+    })
+    .await?;
+
+    Ok(detector.finish(config))
+}
+
+/// Frame-by-frame speech detector over a mono signal
+struct Detector {
+    sample_rate: u64,
+    threshold_db: f64,
+    frame_len: u64,
+    frame_sum: f64,
+    frame_fill: u64,
+    frames: u64,
+    samples: u64,
+    noise_floor_db: Option<f64>,
+    /// First frame of the open segment and the last speech frame in it
+    segment: Option<(u64, u64)>,
+    speech_frames: u64,
+    longest_frames: u64,
+}
+
+impl Detector {
+    fn new(sample_rate: u64, threshold_db: f64) -> Self {
+        Self {
+            sample_rate,
+            threshold_db,
+            frame_len: (sample_rate * FRAME_MS / 1000).max(1),
+            frame_sum: 0.0,
+            frame_fill: 0,
+            frames: 0,
+            samples: 0,
+            noise_floor_db: None,
+            segment: None,
+            speech_frames: 0,
+            longest_frames: 0,
+        }
+    }
+
+    fn close_frame(&mut self) {
+        let mean_square = self.frame_sum / self.frame_fill.max(1) as f64;
+        let level_db = 10.0 * (mean_square + 1e-12).log10();
+        self.frame_sum = 0.0;
+        self.frame_fill = 0;
+
+        // Track the quietest recent level: drop straight to quieter frames,
+        // creep up slowly so sustained speech doesn't become the floor. Starting
+        // no higher than the threshold keeps speech at the very start audible.
+        let floor = match self.noise_floor_db {
+            Some(floor) if level_db < floor => level_db,
+            Some(floor) => floor + FLOOR_RISE_DB_PER_FRAME,
+            None => level_db.min(self.threshold_db),
+        };
+        self.noise_floor_db = Some(floor);
+
+        let speech = level_db > self.threshold_db && level_db > floor + NOISE_MARGIN_DB;
+        let frame = self.frames;
+        self.frames += 1;
+
+        let hangover_frames = HANGOVER_MS / FRAME_MS;
+        match (speech, self.segment) {
+            (true, Some((start, _))) => self.segment = Some((start, frame)),
+            (true, None) => self.segment = Some((frame, frame)),
+            (false, Some((_, last))) if frame - last > hangover_frames => self.close_segment(),
+            _ => {}
+        }
+    }
+
+    fn close_segment(&mut self) {
+        if let Some((start, last)) = self.segment.take() {
+            let frames = last - start + 1;
+            if frames * FRAME_MS >= MIN_SEGMENT_MS {
+                self.speech_frames += frames;
+                self.longest_frames = self.longest_frames.max(frames);
+            }
+        }
+    }
+
+    fn finish(mut self, config: &ScreeningConfig) -> VoiceActivity {
+        if self.frame_fill > 0 {
+            self.close_frame();
+        }
+        self.close_segment();
+
+        let duration_ms = self.samples * 1000 / self.sample_rate;
+        let speech_ms = (self.speech_frames * FRAME_MS).min(duration_ms);
+        let longest_speech_ms = (self.longest_frames * FRAME_MS).min(duration_ms);
+        let speech_ratio = if duration_ms > 0 {
+            speech_ms as f64 / duration_ms as f64
+        } else {
+            0.0
+        };
+
+        VoiceActivity {
+            duration_ms,
+            speech_ms,
+            speech_ratio,
+            longest_speech_ms,
+            likely_empty: speech_ratio < config.min_speech_ratio
+                || longest_speech_ms < config.min_speech_segment_ms,
+        }
+    }
+}
+
+impl MonoSink for Detector {
+    fn push(&mut self, sample: f32) {
+        self.frame_sum += (sample as f64) * (sample as f64);
+        self.frame_fill += 1;
+        self.samples += 1;
+        if self.frame_fill == self.frame_len {
+            self.close_frame();
+        }
+    }
+}
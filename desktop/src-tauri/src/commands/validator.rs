@@ -0,0 +1,273 @@
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use symphonia::core::errors::Error as SymphoniaError;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+
+use crate::error::AppError;
+
+/// Result of checking a copied recording before upload.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValidationOutcome {
+    Valid,
+    /// Header sizes were rewritten in place; the audio data itself is untouched
+    Repaired(String),
+    /// The file can't be uploaded; the reason is shown to staff
+    Corrupt(String),
+}
+
+/// Check container headers for truncation. Only ever call this on the inbox copy:
+/// WAV files with bogus RIFF/data sizes (typically a recorder that lost power
+/// mid-recording) are repaired in place.
+pub async fn validate_recording(path: &str) -> Result<ValidationOutcome, AppError> {
+    let path = path.to_string();
+    tokio::task::spawn_blocking(move || {
+        let path = Path::new(&path);
+        let ext = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_lowercase())
+            .unwrap_or_default();
+
+        match ext.as_str() {
+            "wav" => validate_wav(path),
+            // Not demuxable natively; ffmpeg will report problems during conversion
+            "wma" => Ok(ValidationOutcome::Valid),
+            _ => validate_packets(path),
+        }
+    })
+    .await
+    .map_err(|e| AppError::Other(e.to_string()))?
+}
+
+struct WavLayout {
+    riff_size: u32,
+    block_align: u16,
+    /// Offset of the data chunk's size field
+    data_size_offset: u64,
+    data_start: u64,
+    data_size: u32,
+}
+
+fn validate_wav(path: &Path) -> Result<ValidationOutcome, AppError> {
+    let mut file = File::open(path)?;
+    let file_len = file.metadata()?.len();
+
+    let mut header = [0u8; 12];
+    if file.read_exact(&mut header).is_err() {
+        return Ok(ValidationOutcome::Corrupt("File is too short to be a WAV".into()));
+    }
+    if &header[0..4] == b"RF64" {
+        // 64-bit sizes live in ds64; fall back to a packet scan rather than rewriting them
+        return validate_packets(path);
+    }
+    if &header[0..4] != b"RIFF" || &header[8..12] != b"WAVE" {
+        return Ok(ValidationOutcome::Corrupt("Missing RIFF/WAVE header".into()));
+    }
+
+    let riff_size = u32::from_le_bytes(header[4..8].try_into().unwrap());
+    let layout = match read_wav_layout(&mut file, file_len, riff_size)? {
+        Ok(layout) => layout,
+        Err(reason) => return Ok(ValidationOutcome::Corrupt(reason)),
+    };
+
+    let available = file_len - layout.data_start;
+    let mut fixes = Vec::new();
+    let mut new_data_size = layout.data_size as u64;
+    let mut truncate_to = None;
+
+    if layout.data_size == 0 || layout.data_size as u64 > available {
+        // Size was never finalized: trust the bytes on disk, dropping any partial frame
+        let align = layout.block_align.max(1) as u64;
+        new_data_size = available - available % align;
+        if new_data_size == 0 {
+            return Ok(ValidationOutcome::Corrupt("WAV contains no audio data".into()));
+        }
+        if new_data_size > u32::MAX as u64 {
+            return Ok(ValidationOutcome::Corrupt(
+                "WAV audio data exceeds the 4 GB format limit".into(),
+            ));
+        }
+        fixes.push(format!("data size {} -> {}", layout.data_size, new_data_size));
+        if new_data_size < available {
+            truncate_to = Some(layout.data_start + new_data_size);
+        }
+    } else {
+        // Chunks after the audio (LIST, id3, ...) stay; padding or garbage
+        // past the last complete chunk is not part of the container
+        let data_end = layout.data_start + new_data_size + (new_data_size & 1);
+        let end = chunks_end(&mut file, data_end.min(file_len), file_len)?;
+        if end < file_len {
+            fixes.push(format!("dropped {} trailing bytes", file_len - end));
+            truncate_to = Some(end);
+        }
+    }
+
+    let final_len = truncate_to.unwrap_or(file_len);
+    let expected_riff = final_len - 8;
+    if expected_riff > u32::MAX as u64 {
+        return Ok(ValidationOutcome::Corrupt("WAV exceeds the 4 GB format limit".into()));
+    }
+    if layout.riff_size as u64 != expected_riff {
+        fixes.push(format!("RIFF size {} -> {}", layout.riff_size, expected_riff));
+    }
+
+    if fixes.is_empty() {
+        return Ok(ValidationOutcome::Valid);
+    }
+
+    drop(file);
+    let mut file = OpenOptions::new().write(true).open(path)?;
+    if let Some(len) = truncate_to {
+        file.set_len(len)?;
+    }
+    file.seek(SeekFrom::Start(4))?;
+    file.write_all(&(expected_riff as u32).to_le_bytes())?;
+    file.seek(SeekFrom::Start(layout.data_size_offset))?;
+    file.write_all(&(new_data_size as u32).to_le_bytes())?;
+    file.sync_all()?;
+
+    Ok(ValidationOutcome::Repaired(fixes.join(", ")))
+}
+
+/// Walk the RIFF chunks up to and including `data`. The inner `Err` carries a
+/// reason the file is unrecoverable.
+fn read_wav_layout(
+    file: &mut File,
+    file_len: u64,
+    riff_size: u32,
+) -> Result<Result<WavLayout, String>, AppError> {
+    let mut block_align = None;
+    let mut pos = 12u64;
+
+    while pos + 8 <= file_len {
+        file.seek(SeekFrom::Start(pos))?;
+        let mut chunk_header = [0u8; 8];
+        file.read_exact(&mut chunk_header)?;
+        let size = u32::from_le_bytes(chunk_header[4..8].try_into().unwrap());
+
+        match &chunk_header[0..4] {
+            b"fmt " => {
+                if size < 16 || pos + 8 + 16 > file_len {
+                    return Ok(Err("Truncated fmt chunk".into()));
+                }
+                let mut fmt = [0u8; 16];
+                file.read_exact(&mut fmt)?;
+                block_align = Some(u16::from_le_bytes([fmt[12], fmt[13]]));
+            }
+            b"data" => {
+                let Some(block_align) = block_align else {
+                    return Ok(Err("Missing fmt chunk before audio data".into()));
+                };
+                return Ok(Ok(WavLayout {
+                    riff_size,
+                    block_align,
+                    data_size_offset: pos + 4,
+                    data_start: pos + 8,
+                    data_size: size,
+                }));
+            }
+            _ => {}
+        }
+
+        let next = pos + 8 + size as u64 + (size as u64 & 1);
+        if next > file_len {
+            return Ok(Err(format!(
+                "Truncated '{}' chunk",
+                String::from_utf8_lossy(&chunk_header[0..4])
+            )));
+        }
+        pos = next;
+    }
+
+    Ok(Err("Missing data chunk".into()))
+}
+
+/// End of the last complete chunk from `pos` on. Bytes that don't start a
+/// chunk with a printable ID, or whose chunk runs past the end, end the walk.
+fn chunks_end(file: &mut File, mut pos: u64, file_len: u64) -> Result<u64, AppError> {
+    while pos + 8 <= file_len {
+        file.seek(SeekFrom::Start(pos))?;
+        let mut chunk_header = [0u8; 8];
+        file.read_exact(&mut chunk_header)?;
+        if !chunk_header[0..4].iter().all(|b| b.is_ascii_graphic() || *b == b' ') {
+            break;
+        }
+        let size = u32::from_le_bytes(chunk_header[4..8].try_into().unwrap()) as u64;
+        let next = pos + 8 + size + (size & 1);
+        if next > file_len {
+            // Only the pad byte missing at the end of the file still counts
+            if pos + 8 + size == file_len {
+                pos = file_len;
+            }
+            break;
+        }
+        pos = next;
+    }
+    Ok(pos)
+}
+
+/// Demux every packet (without decoding) and compare against the frame count
+/// declared in the headers.
+fn validate_packets(path: &Path) -> Result<ValidationOutcome, AppError> {
+    let file = File::open(path)?;
+    let mss = MediaSourceStream::new(Box::new(file), Default::default());
+    let mut hint = Hint::new();
+    if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
+        hint.with_extension(ext);
+    }
+
+    let mut format = match symphonia::default::get_probe().format(
+        &hint,
+        mss,
+        &FormatOptions::default(),
+        &MetadataOptions::default(),
+    ) {
+        Ok(probed) => probed.format,
+        Err(e) => return Ok(ValidationOutcome::Corrupt(format!("Unreadable header: {}", e))),
+    };
+
+    let Some(track) = format.default_track() else {
+        return Ok(ValidationOutcome::Corrupt("No audio track".into()));
+    };
+    let track_id = track.id;
+    let declared_frames = track.codec_params.n_frames;
+
+    let mut frames = 0u64;
+    let mut packets = 0u64;
+    loop {
+        match format.next_packet() {
+            Ok(packet) if packet.track_id() == track_id => {
+                frames = frames.max(packet.ts() + packet.dur());
+                packets += 1;
+            }
+            Ok(_) => {}
+            Err(SymphoniaError::IoError(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => break,
+            Err(e) => {
+                return Ok(ValidationOutcome::Corrupt(format!(
+                    "Stream error after {} packets: {}",
+                    packets, e
+                )))
+            }
+        }
+    }
+
+    if packets == 0 {
+        return Ok(ValidationOutcome::Corrupt("Contains no audio packets".into()));
+    }
+    if let Some(declared) = declared_frames {
+        // Allow one packet's worth of slack for encoder padding
+        if declared > 0 && frames + frames / packets < declared {
+            return Ok(ValidationOutcome::Corrupt(format!(
+                "Truncated: {} of {} frames present",
+                frames, declared
+            )));
+        }
+    }
+
+    Ok(ValidationOutcome::Valid)
+}
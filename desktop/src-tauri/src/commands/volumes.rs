@@ -1,4 +1,6 @@
-use serde::Serialize;
+use chrono::{DateTime, Duration, FixedOffset, Local, NaiveDateTime, TimeZone};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
 use std::path::Path;
 use tokio::fs;
 
@@ -22,13 +24,64 @@ pub struct VolumeInfo {
     pub has_recorder_id: bool,
 }
 
-#[derive(Debug, Serialize)]
+/// Newest RECORDER_ID.json schema version this app understands.
+/// Files written before versioning was introduced have no `version` and are treated as 1.
+pub const RECORDER_ID_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RecorderIdentifier {
+    #[serde(default = "default_schema_version")]
+    pub version: u32,
     pub device_id: String,
     pub label: String,
+    #[serde(default)]
     pub org_id_hint: Option<String>,
+    #[serde(default)]
     pub notes: Option<String>,
+    /// Recorder make/model, e.g. "Sony ICD-UX570"
+    #[serde(default)]
+    pub model: Option<String>,
+    /// IANA timezone the recorder clock is set to, e.g. "Asia/Tokyo".
+    /// When absent, the recorder is assumed to share this machine's timezone.
+    #[serde(default)]
+    pub timezone: Option<String>,
+    /// Known clock drift in seconds: positive if the recorder runs ahead of true time
+    #[serde(default)]
+    pub clock_offset_seconds: i64,
+}
+
+fn default_schema_version() -> u32 {
+    1
+}
+
+impl RecorderIdentifier {
+    fn parse_timezone(&self) -> Result<Option<Tz>, AppError> {
+        self.timezone
+            .as_deref()
+            .map(|tz| {
+                tz.parse::<Tz>().map_err(|_| {
+                    AppError::InvalidInput(format!("Unknown timezone in RECORDER_ID.json: {}", tz))
+                })
+            })
+            .transpose()
+    }
+
+    /// Convert a wall-clock time read from the recorder into an absolute timestamp,
+    /// applying the recorder's timezone and correcting its known clock offset.
+    pub fn resolve_local_time(&self, local: NaiveDateTime) -> Option<DateTime<FixedOffset>> {
+        let corrected = local - Duration::seconds(self.clock_offset_seconds);
+        match self.parse_timezone().ok().flatten() {
+            Some(tz) => tz
+                .from_local_datetime(&corrected)
+                .earliest()
+                .map(|dt| dt.fixed_offset()),
+            None => Local
+                .from_local_datetime(&corrected)
+                .earliest()
+                .map(|dt| dt.fixed_offset()),
+        }
+    }
 }
 
 #[tauri::command]
@@ -66,25 +119,37 @@ pub async fn identify_device(mount_path: String) -> Result<RecorderIdentifier, A
     }
 
     let raw = fs::read_to_string(&file_path).await?;
-    let parsed: serde_json::Value = serde_json::from_str(&raw)?;
-
-    let device_id = parsed["deviceId"]
-        .as_str()
-        .ok_or_else(|| AppError::InvalidInput("Missing deviceId in RECORDER_ID.json".into()))?
-        .to_string();
-
-    let label = parsed["label"]
-        .as_str()
-        .ok_or_else(|| AppError::InvalidInput("Missing label in RECORDER_ID.json".into()))?
-        .to_string();
-
-    let org_id_hint = parsed["orgIdHint"].as_str().map(|s| s.to_string());
-    let notes = parsed["notes"].as_str().map(|s| s.to_string());
-
-    Ok(RecorderIdentifier {
-        device_id,
-        label,
-        org_id_hint,
-        notes,
-    })
+    parse_recorder_id(&raw)
+}
+
+/// Parse RECORDER_ID.json, rejecting schema versions newer than this app supports
+/// before attempting to interpret any other field.
+pub fn parse_recorder_id(raw: &str) -> Result<RecorderIdentifier, AppError> {
+    #[derive(Deserialize)]
+    struct VersionProbe {
+        #[serde(default = "default_schema_version")]
+        version: u32,
+    }
+
+    let probe: VersionProbe = serde_json::from_str(raw)?;
+    if probe.version == 0 || probe.version > RECORDER_ID_SCHEMA_VERSION {
+        return Err(AppError::InvalidInput(format!(
+            "Unsupported RECORDER_ID.json version {} (this app supports up to {}). Please update VoiceTrunk.",
+            probe.version, RECORDER_ID_SCHEMA_VERSION
+        )));
+    }
+
+    let identifier: RecorderIdentifier = serde_json::from_str(raw).map_err(|e| {
+        AppError::InvalidInput(format!("Invalid RECORDER_ID.json: {}", e))
+    })?;
+
+    if identifier.device_id.is_empty() {
+        return Err(AppError::InvalidInput("Missing deviceId in RECORDER_ID.json".into()));
+    }
+    if identifier.label.is_empty() {
+        return Err(AppError::InvalidInput("Missing label in RECORDER_ID.json".into()));
+    }
+    identifier.parse_timezone()?;
+
+    Ok(identifier)
 }
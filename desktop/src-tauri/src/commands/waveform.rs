@@ -0,0 +1,146 @@
+use std::path::Path;
+
+use log::warn;
+use serde::{Deserialize, Serialize};
+use tokio::fs;
+
+use crate::commands::converter;
+use crate::commands::pcm::{self, MonoSink};
+use crate::config::read_config;
+use crate::error::AppError;
+
+/// Bump when the peaks layout changes so stale caches are regenerated
+const PEAKS_VERSION: u32 = 1;
+/// Peaks are first taken over windows of this length...
+const BASE_WINDOW_MS: u64 = 10;
+/// ...then merged so a recording of any length has at most this many
+const MAX_PEAKS: usize = 2000;
+
+/// Downsampled waveform of a recording, enough to draw it without decoding audio
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WaveformPeaks {
+    pub version: u32,
+    pub sample_rate: u32,
+    /// Source samples covered by each min/max pair
+    pub samples_per_peak: u64,
+    pub duration_ms: u64,
+    /// Interleaved minimum and maximum of each window, scaled to -127..=127
+    pub peaks: Vec<i8>,
+}
+
+/// Cache file kept next to the audio it describes
+pub fn peaks_path(audio_path: &str) -> String {
+    format!("{}.peaks.json", audio_path)
+}
+
+/// Decode a recording and cache its peaks next to it
+pub async fn generate_waveform(
+    path: &str,
+    ffmpeg_path: Option<&str>,
+) -> Result<WaveformPeaks, AppError> {
+    let builder = pcm::decode_mono(path, ffmpeg_path, PeakBuilder::new).await?;
+    let peaks = builder.finish();
+    fs::write(peaks_path(path), serde_json::to_vec(&peaks)?).await?;
+    Ok(peaks)
+}
+
+/// Read cached peaks, if they are current and newer than the audio
+pub async fn load_cached_waveform(path: &str) -> Option<WaveformPeaks> {
+    let cache = peaks_path(path);
+    let audio_modified = fs::metadata(path).await.ok()?.modified().ok()?;
+    let cache_modified = fs::metadata(&cache).await.ok()?.modified().ok()?;
+    if cache_modified < audio_modified {
+        return None;
+    }
+    let peaks: WaveformPeaks = serde_json::from_slice(&fs::read(&cache).await.ok()?).ok()?;
+    (peaks.version == PEAKS_VERSION).then_some(peaks)
+}
+
+#[tauri::command]
+pub async fn get_waveform(path: String) -> Result<WaveformPeaks, AppError> {
+    if !Path::new(&path).exists() {
+        return Err(AppError::NotFound(format!(
+            "Audio file not found: {}",
+            path
+        )));
+    }
+    if let Some(peaks) = load_cached_waveform(&path).await {
+        return Ok(peaks);
+    }
+
+    let config = read_config().await?;
+    let has_ffmpeg = converter::check_ffmpeg(Some(config.ffmpeg_path.clone())).await?;
+    generate_waveform(&path, has_ffmpeg.then_some(config.ffmpeg_path.as_str()))
+        .await
+        .inspect_err(|e| warn!("Could not draw waveform of {}: {}", path, e))
+}
+
+/// Collects min/max per window while the audio streams past
+struct PeakBuilder {
+    sample_rate: u64,
+    window_len: u64,
+    window_min: f32,
+    window_max: f32,
+    window_fill: u64,
+    samples: u64,
+    windows: Vec<(f32, f32)>,
+}
+
+impl PeakBuilder {
+    fn new(sample_rate: u64) -> Self {
+        Self {
+            sample_rate,
+            window_len: (sample_rate * BASE_WINDOW_MS / 1000).max(1),
+            window_min: 0.0,
+            window_max: 0.0,
+            window_fill: 0,
+            samples: 0,
+            windows: Vec::new(),
+        }
+    }
+
+    fn finish(mut self) -> WaveformPeaks {
+        if self.window_fill > 0 {
+            self.windows.push((self.window_min, self.window_max));
+        }
+
+        let merge = self.windows.len().div_ceil(MAX_PEAKS).max(1);
+        let scale = |v: f32| (v.clamp(-1.0, 1.0) * 127.0).round() as i8;
+        let peaks = self
+            .windows
+            .chunks(merge)
+            .flat_map(|group| {
+                let min = group.iter().map(|w| w.0).fold(f32::INFINITY, f32::min);
+                let max = group.iter().map(|w| w.1).fold(f32::NEG_INFINITY, f32::max);
+                [scale(min), scale(max)]
+            })
+            .collect();
+
+        WaveformPeaks {
+            version: PEAKS_VERSION,
+            sample_rate: self.sample_rate as u32,
+            samples_per_peak: self.window_len * merge as u64,
+            duration_ms: self.samples * 1000 / self.sample_rate,
+            peaks,
+        }
+    }
+}
+
+impl MonoSink for PeakBuilder {
+    fn push(&mut self, sample: f32) {
+        if self.window_fill == 0 {
+            self.window_min = sample;
+            self.window_max = sample;
+        } else {
+            self.window_min = self.window_min.min(sample);
+            self.window_max = self.window_max.max(sample);
+        }
+        self.window_fill += 1;
+        self.samples += 1;
+        if self.window_fill == self.window_len {
+            self.windows.push((self.window_min, self.window_max));
+            self.window_fill = 0;
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::PathBuf;
 use tokio::fs;
 
@@ -18,6 +19,312 @@ pub struct AppConfig {
     pub auto_start: bool,
     #[serde(default = "default_watch_interval_ms")]
     pub watch_interval_ms: u64,
+    /// How files the recorder split from one session are uploaded
+    #[serde(default)]
+    pub split_sequences: SequenceMode,
+    /// Silence-based splitting of long recordings for transcription
+    #[serde(default)]
+    pub chunking: ChunkingConfig,
+    /// Output of the built-in encoder used for WAV files when ffmpeg is unavailable
+    #[serde(default)]
+    pub native_encoding: NativeEncoding,
+    /// Named ffmpeg conversion settings
+    #[serde(default = "default_conversion_profiles")]
+    pub conversion_profiles: Vec<ConversionProfile>,
+    /// Profile used for devices without an entry in `device_profiles`
+    #[serde(default = "default_conversion_profile")]
+    pub conversion_profile: String,
+    /// Device ID -> profile name
+    #[serde(default)]
+    pub device_profiles: HashMap<String, String>,
+    /// Voice-activity screening that flags recordings with little or no speech
+    #[serde(default)]
+    pub screening: ScreeningConfig,
+    /// Cache waveform peaks next to each inbox file during import
+    #[serde(default = "default_generate_waveforms")]
+    pub generate_waveforms: bool,
+    /// Also send the peaks to the server for the web recording page
+    #[serde(default)]
+    pub upload_waveforms: bool,
+    /// Retries of presign, part and completion requests that fail transiently
+    #[serde(default)]
+    pub retry: RetryConfig,
+    /// When large files are split into parts, how big the parts are and how
+    /// many go up at once
+    #[serde(default)]
+    pub multipart: MultipartConfig,
+    /// Cap on upload throughput, shared by all transfers
+    #[serde(default)]
+    pub bandwidth: BandwidthConfig,
+    /// Multipart uploads left unfinished for longer are aborted by the sweeper
+    #[serde(default = "default_stale_multipart_hours")]
+    pub stale_multipart_hours: u64,
+    /// Files uploaded at once across all batches
+    #[serde(default = "default_upload_concurrency")]
+    pub upload_concurrency: usize,
+    /// Timeouts and connection reuse of requests to the server and storage
+    #[serde(default)]
+    pub http: HttpConfig,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConversionProfile {
+    pub name: String,
+    /// ffmpeg audio encoder (`libmp3lame`, `aac`, `libopus`, `flac`, ...)
+    pub codec: String,
+    /// Output file extension, which also selects the container
+    pub container: String,
+    #[serde(default)]
+    pub bitrate_kbps: Option<u32>,
+    /// Resample to this rate; keep the source rate when unset
+    #[serde(default)]
+    pub sample_rate: Option<u32>,
+    /// Channel count to mix to with `ChannelMode::Downmix`
+    #[serde(default)]
+    pub channels: Option<u16>,
+    /// Handling of multi-channel sources; inferred from `channels` when unset
+    #[serde(default)]
+    pub channel_mode: Option<ChannelMode>,
+    /// ffmpeg audio filters, joined into one `-af` chain
+    #[serde(default)]
+    pub filters: Vec<String>,
+    /// A file is converted when any trigger matches it
+    #[serde(default)]
+    pub triggers: Vec<ConversionTrigger>,
+    #[serde(default)]
+    pub preprocess: PreprocessConfig,
+}
+
+impl ConversionProfile {
+    /// Profiles saved before `channel_mode` existed downmix exactly when `channels` is set
+    pub fn channel_mode(&self) -> ChannelMode {
+        self.channel_mode.unwrap_or(match self.channels {
+            Some(_) => ChannelMode::Downmix,
+            None => ChannelMode::Keep,
+        })
+    }
+}
+
+/// What conversion does with the channels of a multi-channel source
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum ChannelMode {
+    /// Keep the source layout, e.g. stereo stays stereo
+    Keep,
+    /// Mix to `channels` (mono when unset)
+    Downmix,
+    /// One mono recording per source channel, e.g. a lapel mic on each side
+    Split,
+}
+
+/// Clean-up applied before encoding; every stage is off unless set
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreprocessConfig {
+    /// High-pass cutoff removing rumble and HVAC noise
+    #[serde(default)]
+    pub highpass_hz: Option<u32>,
+    /// Mains frequency (50 or 60); notches it and its first two harmonics
+    #[serde(default)]
+    pub hum_hz: Option<u32>,
+    /// Noise gate threshold in dBFS
+    #[serde(default)]
+    pub gate_threshold_db: Option<f64>,
+    /// EBU R128 integrated loudness target, e.g. -16
+    #[serde(default)]
+    pub loudness_target_lufs: Option<f64>,
+    #[serde(default = "default_true_peak_dbtp")]
+    pub true_peak_dbtp: f64,
+    #[serde(default = "default_loudness_range_lu")]
+    pub loudness_range_lu: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConversionTrigger {
+    /// File extension, case-insensitive
+    pub format: String,
+    /// Only files at least this large match; 0 matches every size
+    #[serde(default)]
+    pub min_size_mb: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChunkingConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Recordings longer than this are split
+    #[serde(default = "default_max_chunk_minutes")]
+    pub max_chunk_minutes: u64,
+    /// Audio repeated at the start of each chunk after the first
+    #[serde(default = "default_chunk_overlap_ms")]
+    pub overlap_ms: u64,
+    /// Level (dBFS) below which audio counts as silence
+    #[serde(default = "default_silence_threshold_db")]
+    pub silence_threshold_db: f64,
+    /// Shortest silence considered as a cut point
+    #[serde(default = "default_min_silence_ms")]
+    pub min_silence_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScreeningConfig {
+    #[serde(default = "default_screening_enabled")]
+    pub enabled: bool,
+    /// Level (dBFS) audio must exceed to count as speech
+    #[serde(default = "default_speech_threshold_db")]
+    pub speech_threshold_db: f64,
+    /// Recordings with a smaller share of speech are flagged as likely empty
+    #[serde(default = "default_min_speech_ratio")]
+    pub min_speech_ratio: f64,
+    /// Recordings whose longest speech segment is shorter are flagged as likely empty
+    #[serde(default = "default_min_speech_segment_ms")]
+    pub min_speech_segment_ms: u64,
+    /// Keep flagged recordings in the inbox until someone confirms the upload
+    #[serde(default)]
+    pub hold_empty: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RetryConfig {
+    /// Tries per request, including the first; 1 disables retries
+    #[serde(default = "default_retry_max_attempts")]
+    pub max_attempts: u32,
+    /// Wait before the first retry, doubled for each one after it
+    #[serde(default = "default_retry_initial_delay_ms")]
+    pub initial_delay_ms: u64,
+    /// Longest wait between attempts, also the cap on a server's `Retry-After`
+    #[serde(default = "default_retry_max_delay_ms")]
+    pub max_delay_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MultipartConfig {
+    /// Files larger than this are uploaded in parts
+    #[serde(default = "default_multipart_threshold_mb")]
+    pub threshold_mb: u64,
+    /// Size of each part; in adaptive mode the smallest part size used
+    #[serde(default = "default_multipart_part_size_mb")]
+    pub part_size_mb: u64,
+    /// Parts uploaded at once; in adaptive mode the starting point
+    #[serde(default = "default_multipart_concurrency")]
+    pub concurrency: usize,
+    /// Grow parts with the file size, and raise or lower concurrency from the
+    /// throughput and errors seen while uploading
+    #[serde(default = "default_multipart_adaptive")]
+    pub adaptive: bool,
+    /// Most parts adaptive mode lets run at once
+    #[serde(default = "default_multipart_max_concurrency")]
+    pub max_concurrency: usize,
+}
+
+impl MultipartConfig {
+    pub fn threshold_bytes(&self) -> u64 {
+        self.threshold_mb.saturating_mul(1024 * 1024)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct BandwidthConfig {
+    /// Upload cap in Mbit/s; 0 for none
+    #[serde(default)]
+    pub limit_mbps: f64,
+    /// Times of day with their own cap, replacing `limit_mbps`; the first
+    /// window that matches wins
+    #[serde(default)]
+    pub schedule: Vec<BandwidthWindow>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BandwidthWindow {
+    /// Local time the window opens, as `HH:MM`
+    pub start: String,
+    /// Local time the window closes, as `HH:MM`; before `start` to run past midnight
+    pub end: String,
+    /// Cap in Mbit/s during the window; 0 for none
+    pub limit_mbps: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct HttpConfig {
+    /// Longest wait to open a connection
+    #[serde(default = "default_http_connect_timeout_secs")]
+    pub connect_timeout_secs: u64,
+    /// Longest a request may go without progress: an API call waiting for
+    /// its response, or an upload whose body stops moving
+    #[serde(default = "default_http_read_timeout_secs")]
+    pub read_timeout_secs: u64,
+    /// Idle connections are kept open for reuse this long
+    #[serde(default = "default_http_pool_idle_secs")]
+    pub pool_idle_secs: u64,
+    /// Interval of TCP keep-alive probes on open connections; 0 turns them off
+    #[serde(default = "default_http_keepalive_secs")]
+    pub keepalive_secs: u64,
+    /// Use HTTP/2 with servers that offer it
+    #[serde(default = "default_http2")]
+    pub http2: bool,
+    /// Proxy for all requests, e.g. `http://proxy.example.com:8080`, with
+    /// credentials in the URL if it needs them. Unset uses the system proxy.
+    #[serde(default)]
+    pub proxy_url: Option<String>,
+    /// Hosts reached directly rather than through `proxy_url`: names, which
+    /// also match their subdomains, IP addresses or CIDR ranges
+    #[serde(default)]
+    pub no_proxy: Vec<String>,
+    /// PEM files of CA certificates to trust on top of the system's, such as
+    /// the root of a TLS-inspecting proxy
+    #[serde(default)]
+    pub ca_certificates: Vec<String>,
+}
+
+/// Handling of recorder auto-split sequences (`REC001_01.WAV`, `REC001_02.WAV`, ...)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum SequenceMode {
+    /// Upload each part, linked by sequence metadata
+    #[default]
+    Group,
+    /// Join the parts losslessly into one recording before upload
+    Stitch,
+    /// Treat every file as an independent recording
+    Off,
+}
+
+/// Built-in (ffmpeg-free) WAV encoding
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum NativeEncoding {
+    /// 64 kbit/s MP3 at 16 kHz mono, matching the ffmpeg speech profile
+    #[default]
+    Compact,
+    /// FLAC at the original sample rate, channels and bit depth
+    Lossless,
+}
+
+impl NativeEncoding {
+    /// Recorded as the conversion profile of files this encoder produced
+    pub fn profile_name(self) -> &'static str {
+        match self {
+            NativeEncoding::Compact => "builtin-mp3",
+            NativeEncoding::Lossless => "builtin-lossless",
+        }
+    }
+
+    /// Extension of the files this encoder produces
+    pub fn extension(self) -> &'static str {
+        match self {
+            NativeEncoding::Compact => "mp3",
+            NativeEncoding::Lossless => "flac",
+        }
+    }
 }
 
 fn default_max_storage_gb() -> u64 {
@@ -32,6 +339,215 @@ fn default_auto_import() -> bool {
 fn default_watch_interval_ms() -> u64 {
     3000
 }
+fn default_true_peak_dbtp() -> f64 {
+    -1.5
+}
+fn default_loudness_range_lu() -> f64 {
+    11.0
+}
+fn default_conversion_profile() -> String {
+    "speech".to_string()
+}
+fn default_conversion_triggers() -> Vec<ConversionTrigger> {
+    vec![
+        ConversionTrigger {
+            format: "wma".to_string(),
+            min_size_mb: 0,
+        },
+        ConversionTrigger {
+            format: "wav".to_string(),
+            min_size_mb: 50,
+        },
+    ]
+}
+fn default_conversion_profiles() -> Vec<ConversionProfile> {
+    vec![
+        ConversionProfile {
+            name: default_conversion_profile(),
+            codec: "libmp3lame".to_string(),
+            container: "mp3".to_string(),
+            bitrate_kbps: Some(64),
+            sample_rate: Some(16000),
+            channels: Some(1),
+            channel_mode: Some(ChannelMode::Downmix),
+            filters: Vec::new(),
+            triggers: default_conversion_triggers(),
+            preprocess: PreprocessConfig::default(),
+        },
+        // Keeps the stereo image and high frequencies that diarization relies on
+        ConversionProfile {
+            name: "multi-speaker".to_string(),
+            codec: "libmp3lame".to_string(),
+            container: "mp3".to_string(),
+            bitrate_kbps: Some(128),
+            sample_rate: Some(44100),
+            channels: None,
+            channel_mode: Some(ChannelMode::Keep),
+            filters: Vec::new(),
+            triggers: default_conversion_triggers(),
+            preprocess: PreprocessConfig::default(),
+        },
+        // For recorders with a lapel mic on each channel: keeps speakers apart
+        ConversionProfile {
+            name: "dual-mic".to_string(),
+            codec: "libmp3lame".to_string(),
+            container: "mp3".to_string(),
+            bitrate_kbps: Some(64),
+            sample_rate: Some(16000),
+            channels: None,
+            channel_mode: Some(ChannelMode::Split),
+            filters: Vec::new(),
+            triggers: default_conversion_triggers(),
+            preprocess: PreprocessConfig::default(),
+        },
+    ]
+}
+fn default_max_chunk_minutes() -> u64 {
+    60
+}
+fn default_chunk_overlap_ms() -> u64 {
+    2000
+}
+fn default_silence_threshold_db() -> f64 {
+    -35.0
+}
+fn default_min_silence_ms() -> u64 {
+    800
+}
+fn default_generate_waveforms() -> bool {
+    true
+}
+fn default_screening_enabled() -> bool {
+    true
+}
+fn default_speech_threshold_db() -> f64 {
+    -45.0
+}
+fn default_min_speech_ratio() -> f64 {
+    0.02
+}
+fn default_min_speech_segment_ms() -> u64 {
+    1500
+}
+fn default_stale_multipart_hours() -> u64 {
+    72
+}
+fn default_upload_concurrency() -> usize {
+    2
+}
+fn default_multipart_threshold_mb() -> u64 {
+    100
+}
+fn default_multipart_part_size_mb() -> u64 {
+    10
+}
+fn default_multipart_concurrency() -> usize {
+    4
+}
+fn default_multipart_adaptive() -> bool {
+    true
+}
+fn default_multipart_max_concurrency() -> usize {
+    8
+}
+fn default_http_connect_timeout_secs() -> u64 {
+    10
+}
+fn default_http_read_timeout_secs() -> u64 {
+    60
+}
+fn default_http_pool_idle_secs() -> u64 {
+    90
+}
+fn default_http_keepalive_secs() -> u64 {
+    30
+}
+fn default_http2() -> bool {
+    true
+}
+fn default_retry_max_attempts() -> u32 {
+    5
+}
+fn default_retry_initial_delay_ms() -> u64 {
+    1000
+}
+fn default_retry_max_delay_ms() -> u64 {
+    30_000
+}
+
+impl Default for PreprocessConfig {
+    fn default() -> Self {
+        Self {
+            highpass_hz: None,
+            hum_hz: None,
+            gate_threshold_db: None,
+            loudness_target_lufs: None,
+            true_peak_dbtp: default_true_peak_dbtp(),
+            loudness_range_lu: default_loudness_range_lu(),
+        }
+    }
+}
+
+impl Default for ChunkingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_chunk_minutes: default_max_chunk_minutes(),
+            overlap_ms: default_chunk_overlap_ms(),
+            silence_threshold_db: default_silence_threshold_db(),
+            min_silence_ms: default_min_silence_ms(),
+        }
+    }
+}
+
+impl Default for ScreeningConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_screening_enabled(),
+            speech_threshold_db: default_speech_threshold_db(),
+            min_speech_ratio: default_min_speech_ratio(),
+            min_speech_segment_ms: default_min_speech_segment_ms(),
+            hold_empty: false,
+        }
+    }
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: default_retry_max_attempts(),
+            initial_delay_ms: default_retry_initial_delay_ms(),
+            max_delay_ms: default_retry_max_delay_ms(),
+        }
+    }
+}
+
+impl Default for MultipartConfig {
+    fn default() -> Self {
+        Self {
+            threshold_mb: default_multipart_threshold_mb(),
+            part_size_mb: default_multipart_part_size_mb(),
+            concurrency: default_multipart_concurrency(),
+            adaptive: default_multipart_adaptive(),
+            max_concurrency: default_multipart_max_concurrency(),
+        }
+    }
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self {
+            connect_timeout_secs: default_http_connect_timeout_secs(),
+            read_timeout_secs: default_http_read_timeout_secs(),
+            pool_idle_secs: default_http_pool_idle_secs(),
+            keepalive_secs: default_http_keepalive_secs(),
+            http2: default_http2(),
+            proxy_url: None,
+            no_proxy: Vec::new(),
+            ca_certificates: Vec::new(),
+        }
+    }
+}
 
 impl Default for AppConfig {
     fn default() -> Self {
@@ -42,10 +558,41 @@ impl Default for AppConfig {
             auto_import: default_auto_import(),
             auto_start: false,
             watch_interval_ms: default_watch_interval_ms(),
+            split_sequences: SequenceMode::default(),
+            chunking: ChunkingConfig::default(),
+            native_encoding: NativeEncoding::default(),
+            conversion_profiles: default_conversion_profiles(),
+            conversion_profile: default_conversion_profile(),
+            device_profiles: HashMap::new(),
+            screening: ScreeningConfig::default(),
+            generate_waveforms: default_generate_waveforms(),
+            upload_waveforms: false,
+            retry: RetryConfig::default(),
+            multipart: MultipartConfig::default(),
+            bandwidth: BandwidthConfig::default(),
+            stale_multipart_hours: default_stale_multipart_hours(),
+            upload_concurrency: default_upload_concurrency(),
+            http: HttpConfig::default(),
         }
     }
 }
 
+impl AppConfig {
+    /// Profile for files from `device_id`: its `device_profiles` entry, else the
+    /// default profile. Unknown names fall back to the built-in speech profile.
+    pub fn conversion_profile_for(&self, device_id: &str) -> ConversionProfile {
+        let name = self
+            .device_profiles
+            .get(device_id)
+            .unwrap_or(&self.conversion_profile);
+        self.conversion_profiles
+            .iter()
+            .find(|p| &p.name == name)
+            .cloned()
+            .unwrap_or_else(|| default_conversion_profiles().remove(0))
+    }
+}
+
 pub fn get_base_path() -> PathBuf {
     dirs::home_dir()
         .expect("Could not determine home directory")
@@ -12,7 +12,24 @@ pub enum AppError {
     Http(#[from] reqwest::Error),
 
     #[error("API error ({status}): {message}")]
-    Api { status: u16, message: String },
+    Api {
+        status: u16,
+        message: String,
+        /// Wait the server asked for with `Retry-After`
+        retry_after: Option<std::time::Duration>,
+    },
+
+    /// A transfer stopped making progress
+    #[error("Timed out: {0}")]
+    Timeout(String),
+
+    /// What the server holds after an upload differs from the file sent
+    #[error("Upload verification failed: {0}")]
+    Mismatch(String),
+
+    /// Storage holds nothing for an upload that was sent, so it can go again
+    #[error("Upload not received: {0}")]
+    NotReceived(String),
 
     #[error("Not found: {0}")]
     NotFound(String),
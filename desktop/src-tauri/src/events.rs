@@ -34,7 +34,7 @@ pub struct HashProgress {
     pub total_bytes: u64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct UploadProgress {
     pub recording_id: String,
@@ -43,4 +43,23 @@ pub struct UploadProgress {
     pub total_bytes: u64,
     pub part_number: Option<u32>,
     pub total_parts: Option<u32>,
+    /// Set on entries announcing a retry: the attempt about to start
+    pub attempt: Option<u32>,
+    /// Wait before that attempt
+    pub retry_delay_ms: Option<u64>,
+    /// Failure that caused the retry
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConversionProgress {
+    pub batch_id: String,
+    pub file_name: String,
+    /// Audio converted so far
+    pub out_time_ms: u64,
+    pub percent: Option<f64>,
+    /// Multiple of real time
+    pub speed: Option<f64>,
+    pub eta_seconds: Option<u64>,
 }
@@ -2,6 +2,8 @@ mod commands;
 mod config;
 mod error;
 mod events;
+mod multipart_sweeper;
+mod network_monitor;
 mod state;
 mod tray;
 mod volume_watcher;
@@ -27,12 +29,33 @@ pub fn run() {
             });
             app.manage(new_managed_state(state));
 
+            // Apply the saved upload bandwidth cap
+            let config = tauri::async_runtime::block_on(async {
+                config::read_config().await.unwrap_or_default()
+            });
+            commands::bandwidth::configure(&config.bandwidth);
+
+            // Shared upload queue for all batches
+            app.manage(commands::upload_queue::new_upload_queue(
+                app.handle().clone(),
+                config.upload_concurrency,
+            ));
+
+            // Connections to the server, reused by every request
+            app.manage(commands::http::ManagedHttp::default());
+
             // Set up system tray
             tray::setup_tray(app.handle())?;
 
             // Start volume watcher
             volume_watcher::start_volume_watcher(app.handle().clone());
 
+            // Abort multipart uploads left behind by earlier sessions
+            multipart_sweeper::start_multipart_sweeper(app.handle().clone());
+
+            // Resume batches waiting for the network once the server answers
+            network_monitor::start_network_monitor(app.handle().clone());
+
             info!("VoiceTrunk desktop app started");
 
             Ok(())
@@ -56,10 +79,27 @@ pub fn run() {
             commands::converter::detect_ffmpeg_path,
             commands::converter::needs_conversion,
             commands::converter::convert_audio,
+            // Probe
+            commands::probe::probe_audio,
+            // Waveform
+            commands::waveform::get_waveform,
             // Importer
             commands::importer::start_import,
             commands::importer::cancel_import,
             commands::importer::upload_files,
+            commands::importer::resolve_held_file,
+            // Bandwidth
+            commands::bandwidth::get_bandwidth_status,
+            commands::bandwidth::set_bandwidth_limit,
+            // Upload queue
+            commands::upload_queue::get_upload_queue,
+            commands::upload_queue::set_upload_priority,
+            commands::upload_queue::move_upload,
+            commands::upload_queue::set_upload_paused,
+            commands::upload_queue::cancel_upload,
+            // Network
+            commands::network::get_offline_backlog,
+            commands::network::check_connection,
             // Batches
             commands::batches::get_batches,
             commands::batches::clean_completed_batches,
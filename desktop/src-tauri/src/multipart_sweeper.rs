@@ -0,0 +1,109 @@
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use log::{info, warn};
+use tauri::{AppHandle, Manager};
+
+use crate::commands::api_client;
+use crate::commands::http::ManagedHttp;
+use crate::commands::importer::{is_batch_active, read_auth};
+use crate::commands::uploader::clear_multipart;
+use crate::config::read_config;
+use crate::error::AppError;
+use crate::state::{persist_state, ManagedState, ManagedStateInner};
+
+/// Wait after startup before the first sweep, so resumed work claims its batches first
+const INITIAL_DELAY: Duration = Duration::from_secs(60);
+const SWEEP_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// A multipart upload left behind in the batch state
+struct Abandoned {
+    batch_id: String,
+    sha256: String,
+    recording_id: String,
+    upload_id: String,
+    /// The file went up anyway, so only the local record is left to clear
+    uploaded: bool,
+}
+
+/// Periodically abort multipart uploads that were abandoned: left by
+/// discarded files, or started longer ago than `staleMultipartHours`.
+/// Storage keeps the parts of an unfinished upload until it is aborted.
+pub fn start_multipart_sweeper(app_handle: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(INITIAL_DELAY).await;
+        loop {
+            let managed_state = app_handle.state::<ManagedState>().inner().clone();
+            if let Err(e) = sweep_multipart_uploads(&app_handle, &managed_state).await {
+                warn!("Multipart upload sweep failed: {}", e);
+            }
+            tokio::time::sleep(SWEEP_INTERVAL).await;
+        }
+    });
+}
+
+pub async fn sweep_multipart_uploads(
+    app_handle: &AppHandle,
+    managed_state: &ManagedStateInner,
+) -> Result<(), AppError> {
+    let config = read_config().await?;
+    let cutoff = Utc::now() - chrono::Duration::hours(config.stale_multipart_hours as i64);
+
+    let abandoned: Vec<Abandoned> = {
+        let state = managed_state.inner.lock().unwrap();
+        state
+            .batches
+            .iter()
+            .filter(|(batch_id, _)| !is_batch_active(batch_id))
+            .flat_map(|(batch_id, batch)| {
+                batch.files.iter().filter_map(move |(sha256, file)| {
+                    let upload_id = file.multipart_upload_id.clone()?;
+                    let stale = file
+                        .multipart_started_at
+                        .as_deref()
+                        .and_then(|at| DateTime::parse_from_rfc3339(at).ok())
+                        .is_none_or(|at| at < cutoff);
+                    (file.uploaded || file.discarded || stale).then(|| Abandoned {
+                        batch_id: batch_id.clone(),
+                        sha256: sha256.clone(),
+                        recording_id: file.recording_id.clone(),
+                        upload_id,
+                        uploaded: file.uploaded,
+                    })
+                })
+            })
+            .collect()
+    };
+    if abandoned.is_empty() {
+        return Ok(());
+    }
+
+    let api = app_handle
+        .state::<ManagedHttp>()
+        .client(&config, &read_auth(app_handle))?;
+    for upload in &abandoned {
+        if !upload.uploaded {
+            let result = api_client::abort_multipart(
+                &api,
+                upload.recording_id.clone(),
+                upload.upload_id.clone(),
+            )
+            .await;
+            match result {
+                // Already gone on the server
+                Ok(()) | Err(AppError::Api { status: 404, .. }) => {
+                    info!("Aborted stale multipart upload for {}", upload.recording_id)
+                }
+                Err(e) => {
+                    warn!(
+                        "Could not abort multipart upload for {}: {}",
+                        upload.recording_id, e
+                    );
+                    continue;
+                }
+            }
+        }
+        clear_multipart(managed_state, &upload.batch_id, &upload.sha256);
+    }
+    persist_state(managed_state).await
+}
@@ -0,0 +1,78 @@
+use std::time::Duration;
+
+use log::{info, warn};
+use tauri::{AppHandle, Manager};
+
+use crate::commands::http::ManagedHttp;
+use crate::commands::importer::{is_batch_active, read_auth, resume_offline_batch};
+use crate::commands::network;
+use crate::commands::upload_queue::ManagedQueue;
+use crate::config::read_config;
+use crate::state::{BatchStatus, ManagedState};
+
+const PROBE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Periodically probe the server while batches wait for the network, and
+/// resume their preflight and upload once it answers. Batches left waiting
+/// by an earlier session are picked up too.
+pub fn start_network_monitor(app_handle: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(PROBE_INTERVAL).await;
+
+            let managed_state = app_handle.state::<ManagedState>().inner().clone();
+            let waiting: Vec<String> = {
+                let state = managed_state.inner.lock().unwrap();
+                state
+                    .batches
+                    .iter()
+                    .filter(|(batch_id, batch)| {
+                        batch.status == BatchStatus::WaitingForNetwork && !is_batch_active(batch_id)
+                    })
+                    .map(|(batch_id, _)| batch_id.clone())
+                    .collect()
+            };
+            if waiting.is_empty() {
+                continue;
+            }
+
+            let config = match read_config().await {
+                Ok(config) => config,
+                Err(e) => {
+                    warn!("Could not read config to probe the server: {}", e);
+                    continue;
+                }
+            };
+            let http = app_handle.state::<ManagedHttp>().inner().clone();
+            let api = match http.client(&config, &read_auth(&app_handle)) {
+                Ok(api) => api,
+                Err(e) => {
+                    warn!("Could not set up a client to probe the server: {}", e);
+                    continue;
+                }
+            };
+            let online = network::probe(&api).await;
+            network::emit_backlog(&app_handle, &managed_state);
+            if !online {
+                continue;
+            }
+
+            let queue = app_handle.state::<ManagedQueue>().inner().clone();
+            for batch_id in waiting {
+                info!("Server reachable, resuming batch {}", batch_id);
+                let app_handle = app_handle.clone();
+                let managed_state = managed_state.clone();
+                let queue = queue.clone();
+                let http = http.clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) =
+                        resume_offline_batch(&batch_id, &app_handle, &managed_state, &queue, &http)
+                            .await
+                    {
+                        warn!("Could not resume batch {}: {}", batch_id, e);
+                    }
+                });
+            }
+        }
+    });
+}
@@ -3,6 +3,13 @@ use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 use tokio::fs;
 
+use crate::commands::api_client::CompletedPart;
+use crate::commands::converter::{ChannelInfo, LoudnessStats};
+use crate::commands::probe::AudioProbe;
+use crate::commands::scanner::SequenceInfo;
+use crate::commands::splitter::ChunkInfo;
+use crate::commands::timestamps::TimestampSource;
+use crate::commands::vad::VoiceActivity;
 use crate::config::get_base_path;
 use crate::error::AppError;
 
@@ -13,23 +20,158 @@ pub enum BatchStatus {
     Uploading,
     Completed,
     PartialError,
+    /// Everything else is done; held recordings wait for confirmation
+    AwaitingReview,
+    /// Local stages are done; preflight or upload waits for the server to be reachable
+    WaitingForNetwork,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct FileStatus {
     pub recording_id: String,
+    /// Set once the file is in storage, whether sent or already on the server
     pub uploaded: bool,
+    /// Set once the server's record of the upload matched the file sent
+    #[serde(default)]
+    pub verified: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub upload_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub raw_r2_key: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub completed_parts: Option<Vec<u32>>,
+    /// Parts of the multipart upload already stored, with the ETags needed
+    /// to complete it
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_completed_parts"
+    )]
+    pub completed_parts: Option<Vec<CompletedPart>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub multipart_upload_id: Option<String>,
+    /// Part size the multipart upload was started with, kept for resume
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub multipart_part_size: Option<u64>,
+    /// When the multipart upload was started, for the stale upload sweep
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub multipart_started_at: Option<String>,
+    /// Recording start time sent to the server as `recorderFileCreatedAt`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recorded_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recorded_at_source: Option<TimestampSource>,
+    /// Hash of the file as read from the card
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub original_sha256: Option<String>,
+    /// Set when the inbox copy's headers were repaired before upload
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repaired_sha256: Option<String>,
+    /// Hash of the bytes actually uploaded, after repair and conversion. Storage
+    /// rejects the PUT if what it receives does not match.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub upload_sha256: Option<String>,
+    /// Checksum storage should report for the file once sent: its upload
+    /// hash, or the composite of the parts' for a multipart upload. Kept so a
+    /// file sent before verification was cut off only needs verifying.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stored_sha256: Option<String>,
+    /// Stream properties of the uploaded file
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub audio: Option<AudioProbe>,
+    /// Set when this file is one part of a recorder auto-split sequence
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sequence: Option<SequenceInfo>,
+    /// Original file names of the parts joined into this recording
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stitched_from: Option<Vec<String>>,
+    /// Set when this file is one channel split from a multi-channel recording
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub channel: Option<ChannelInfo>,
+    /// Silence-cut pieces uploaded alongside the recording for transcription
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chunks: Option<Vec<ChunkInfo>>,
+    /// Name of the conversion profile applied before upload
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub conversion_profile: Option<String>,
+    /// Source loudness measured during normalization
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub loudness: Option<LoudnessStats>,
+    /// Speech content measured during import
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub voice_activity: Option<VoiceActivity>,
+    /// Inbox file that is uploaded; its waveform peaks are cached next to it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub local_path: Option<String>,
+    /// Set while a likely-empty recording waits for someone to confirm its upload
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub held: Option<HeldUpload>,
+    /// Left out of the upload after review
+    #[serde(default)]
+    pub discarded: bool,
+    /// Set while the file waits for the server to be reachable again, to be
+    /// preflighted if it has not been and then uploaded
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub waiting_for_network: Option<HeldUpload>,
+}
+
+impl FileStatus {
+    /// Nothing is left to do for the file: it is in storage with no failed
+    /// verification or wait outstanding, or it was left out after review
+    pub fn is_done(&self) -> bool {
+        self.discarded
+            || (self.uploaded && self.error.is_none() && self.waiting_for_network.is_none())
+    }
+}
+
+/// State files written before ETags were kept list bare part numbers. Those
+/// parts are dropped here; resuming recovers them from the server's listing.
+fn deserialize_completed_parts<'de, D>(
+    deserializer: D,
+) -> Result<Option<Vec<CompletedPart>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum StoredPart {
+        Part(CompletedPart),
+        Legacy(serde::de::IgnoredAny),
+    }
+
+    let parts: Option<Vec<StoredPart>> = Option::deserialize(deserializer)?;
+    Ok(parts.map(|parts| {
+        parts
+            .into_iter()
+            .filter_map(|part| match part {
+                StoredPart::Part(part) => Some(part),
+                StoredPart::Legacy(_) => None,
+            })
+            .collect()
+    }))
+}
+
+/// The inbox file behind a recording whose upload waits, for confirmation or
+/// for the network, kept so it can be uploaded later
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HeldUpload {
+    pub local_path: String,
+    pub original_file_name: String,
+    /// Length of the file at `local_path`, which is what gets uploaded
+    pub size_bytes: u64,
+    /// Size the server records for the recording, when preflighted from here;
+    /// a converted file is recorded at its size on the card
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub record_size_bytes: Option<u64>,
+}
+
+impl HeldUpload {
+    /// Size to preflight the recording with
+    pub fn record_size(&self) -> u64 {
+        self.record_size_bytes.unwrap_or(self.size_bytes)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
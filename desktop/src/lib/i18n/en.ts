@@ -12,6 +12,7 @@ export const en = {
   ffmpeg_not_found: "ffmpeg was not detected",
   ffmpeg_not_found_detail: "ffmpeg is required for audio conversion. Please specify the path in settings.",
   importing: "Importing",
+  conversion_remaining: "remaining",
   files_count: "files",
   connected_devices: "Connected Devices",
   no_devices: "No devices connected",
@@ -31,7 +32,9 @@ export const en = {
   processing: "Processing...",
   start_upload: "Start Upload",
   hashing: "Hashing...",
+  retrying: "Retrying",
   done: "Done",
+  verified: "Verified",
   error: "Error",
   pending: "Pending",
   remove: "Remove",
@@ -59,6 +62,112 @@ export const en = {
   auto_import_desc: "Automatically start import when device is connected",
   auto_start: "Start at Login",
   auto_start_desc: "Automatically start app on system boot",
+  split_sequences: "Split Recordings",
+  split_sequences_desc: "How to handle sessions the recorder split into several files (e.g. REC001_01.WAV, REC001_02.WAV)",
+  split_sequences_group: "Upload parts linked in order",
+  split_sequences_stitch: "Join parts into one recording",
+  split_sequences_off: "Treat each file separately",
+  chunking: "Split Long Recordings",
+  chunking_desc: "Cut recordings longer than the limit at silences and upload the pieces for transcription",
+  chunking_max_minutes: "Max chunk length (minutes)",
+  chunking_threshold_db: "Silence threshold (dB)",
+  chunking_min_silence_ms: "Min silence (ms)",
+  native_encoding: "Built-in Encoder",
+  native_encoding_desc: "Used for WAV files when ffmpeg is not available. WMA files still need ffmpeg.",
+  native_encoding_compact: "Compact (MP3, 16 kHz mono)",
+  native_encoding_lossless: "Lossless (FLAC, original quality)",
+  conversion_profile: "Conversion Profile",
+  conversion_profile_desc: "Codec, bitrate, sample rate, channels and which files get converted. Profiles are defined in config.json.",
+  channel_mode: "Channel Handling",
+  channel_mode_desc: "For the selected profile. Split turns each channel of a multi-channel recording (e.g. a lapel mic on each side) into its own linked mono recording.",
+  channel_mode_keep: "Keep (stereo stays stereo)",
+  channel_mode_downmix: "Downmix",
+  channel_mode_split: "Split into one recording per channel",
+  device_profiles: "Per-Device Profiles",
+  device_profiles_desc: "Recorders listed here use their own profile instead of the default",
+  device_id: "Device ID",
+  add_device_profile: "Add Device",
+  screening: "Flag Empty Recordings",
+  screening_desc: "Measure how much of each recording is speech and flag ones that are likely empty",
+  screening_threshold_db: "Speech level (dB)",
+  screening_min_ratio: "Min speech ratio (0-1)",
+  screening_min_segment_ms: "Min speech stretch (ms)",
+  screening_hold: "Hold Likely-Empty Recordings",
+  screening_hold_desc: "Keep flagged recordings in the inbox until you confirm the upload on the Batches page",
+  generate_waveforms: "Waveforms",
+  generate_waveforms_desc: "Draw a waveform of each imported recording and cache it in the inbox",
+  upload_waveforms: "Upload Waveforms",
+  upload_waveforms_desc: "Send waveforms to the server so the recording page can show them",
+  retry_attempts: "Upload attempts",
+  retry_initial_delay_ms: "First retry after (ms)",
+  retry_max_delay_ms: "Longest wait (ms)",
+  retry_desc: "Dropped connections, server errors and rate limits are retried with growing waits",
+  multipart_adaptive: "Adaptive multipart uploads",
+  multipart_adaptive_desc: "Use bigger parts for bigger files, and tune parallel parts to the measured speed and errors",
+  multipart_threshold_mb: "Split files over (MB)",
+  multipart_part_size_mb: "Part size (MB)",
+  multipart_min_part_size_mb: "Smallest part (MB)",
+  multipart_concurrency: "Parallel parts",
+  multipart_start_concurrency: "Starting parallel parts",
+  multipart_max_concurrency: "Most parallel parts",
+  bandwidth_limit_mbps: "Upload speed limit (Mbps)",
+  bandwidth_limit_desc: "Shared by all uploads; 0 for no limit",
+  bandwidth_schedule: "Scheduled limits",
+  bandwidth_schedule_desc: "Replace the limit above during these local times; a window ending before it starts runs past midnight",
+  bandwidth_window_start: "From",
+  bandwidth_window_end: "Until",
+  bandwidth_add_window: "Add time window",
+  upload_bandwidth: "Upload bandwidth",
+  bandwidth_unlimited: "No limit",
+  bandwidth_current: "Current limit",
+  bandwidth_overridden: "Set here until cleared",
+  bandwidth_throttle: "Throttle",
+  bandwidth_unthrottle: "Remove limit",
+  bandwidth_use_settings: "Use settings",
+  stale_multipart_hours: "Abandon unfinished uploads after (hours)",
+  stale_multipart_hours_desc: "Interrupted large uploads can resume until then; after that their stored parts are deleted",
+  upload_concurrency: "Files uploaded at once",
+  upload_concurrency_desc: "Shared by all imports and manual uploads",
+  http_connect_timeout_secs: "Connect timeout (s)",
+  http_read_timeout_secs: "Stall timeout (s)",
+  http_pool_idle_secs: "Keep idle connections (s)",
+  http_keepalive_secs: "TCP keep-alive (s)",
+  http_timeouts_desc: "A request that makes no progress for the stall timeout fails and is retried. Set keep-alive to 0 to turn it off",
+  http2: "HTTP/2",
+  http2_desc: "Use HTTP/2 with servers that offer it, sending many requests over one connection",
+  http_proxy_url: "Proxy",
+  http_proxy_url_desc: "Used for the server and for uploads to storage. Leave empty to use the system proxy; put credentials in the URL if the proxy needs them",
+  http_no_proxy: "Bypass proxy for",
+  http_no_proxy_desc: "Hosts reached directly, separated by commas. A name also covers its subdomains; IP ranges can be given as CIDR",
+  http_ca_certificates: "Additional trusted CA certificates",
+  http_ca_certificates_desc: "Paths of PEM files, one per line, trusted on top of the system's, such as the root of a TLS-inspecting proxy",
+  connection_via_proxy: "The connection went through the configured proxy",
+  connection_problem_invalidSettings: "The proxy URL or a CA certificate file can't be used. Check the path and that the file is a PEM certificate.",
+  connection_problem_dns: "The server name could not be found. Check the server URL and your network or DNS settings.",
+  connection_problem_proxy: "The proxy refused the connection or asked for credentials. Check the proxy URL and include a user name and password if it needs them.",
+  connection_problem_untrustedCertificate: "The server's certificate is signed by an authority this computer doesn't trust. Networks that inspect HTTPS traffic do this; ask your IT department for their root certificate and add it under additional trusted CA certificates.",
+  connection_problem_certificateHostMismatch: "The server's certificate is for a different host name. Check that the server URL matches the name on the certificate.",
+  connection_problem_certificateExpired: "The server's certificate has expired or isn't valid yet. Check this computer's clock; if it is right, the certificate needs renewing.",
+  connection_problem_tls: "The secure connection could not be set up. A proxy or firewall may be interfering with HTTPS.",
+  connection_problem_timeout: "The server did not answer in time.",
+  connection_problem_unreachable: "The server could not be reached. Check the server URL and your network connection.",
+  upload_queue: "Upload queue",
+  queue_waiting: "Waiting",
+  queue_paused: "Paused",
+  queue_uploading: "Uploading",
+  priority_low: "Low",
+  priority_normal: "Normal",
+  priority_high: "High",
+  queue_move_up: "Move up",
+  queue_move_down: "Move down",
+  queue_pause: "Pause",
+  queue_resume: "Resume",
+  queue_cancel: "Cancel",
+  offline_backlog: "Waiting for network",
+  offline_backlog_batches: "Batches",
+  offline_unreachable: "Server unreachable; checking every 30 seconds",
+  offline_reachable: "Server reachable; resuming uploads",
+  offline_checked_at: "Last checked",
   saving: "Saving...",
   save: "Save",
   saved: "Saved",
@@ -85,14 +194,28 @@ export const en = {
   // FileList
   uploading: "Uploading",
   no_files: "No files",
+  held: "Awaiting review",
+  discarded: "Discarded",
+  waiting_for_network: "Waiting for network",
+  likely_empty: "Likely empty",
+  speech: "speech",
+  upload_anyway: "Upload",
+  discard: "Discard",
+  channel: "Channel",
 
   // ImportLog phases
   phase_scanning: "Scanning files",
   phase_copying: "Copying",
+  phase_validating: "Validating",
+  phase_stitching: "Joining split recording",
   phase_converting: "Converting",
+  phase_probing: "Inspecting audio",
+  phase_screening: "Checking for speech",
+  phase_splitting: "Splitting at silences",
   phase_hashing: "Hashing",
   phase_preflight: "Preflight check",
   phase_uploading: "Uploading",
+  phase_waiting_for_network: "Waiting for network; uploads start when the server can be reached",
   phase_completing: "Completing",
   phase_done: "Done",
   phase_error: "Error",
@@ -14,6 +14,7 @@ export const ja: Record<TranslationKey, string> = {
   ffmpeg_not_found: "ffmpeg が検出されませんでした",
   ffmpeg_not_found_detail: "音声変換を行うにはffmpegが必要です。設定ページでパスを指定してください。",
   importing: "インポート中",
+  conversion_remaining: "残り",
   files_count: "ファイル",
   connected_devices: "接続デバイス",
   no_devices: "デバイスが接続されていません",
@@ -33,7 +34,9 @@ export const ja: Record<TranslationKey, string> = {
   processing: "処理中...",
   start_upload: "アップロード開始",
   hashing: "ハッシュ計算中",
+  retrying: "再試行中",
   done: "完了",
+  verified: "検証済み",
   error: "エラー",
   pending: "待機中",
   remove: "削除",
@@ -61,6 +64,112 @@ export const ja: Record<TranslationKey, string> = {
   auto_import_desc: "デバイス接続時に自動でインポートを開始",
   auto_start: "ログイン時に起動",
   auto_start_desc: "システム起動時にアプリを自動起動",
+  split_sequences: "分割録音",
+  split_sequences_desc: "レコーダーが複数ファイルに分割したセッション（例: REC001_01.WAV, REC001_02.WAV）の扱い",
+  split_sequences_group: "順序を紐付けて個別にアップロード",
+  split_sequences_stitch: "1つの録音に結合",
+  split_sequences_off: "各ファイルを別々の録音として扱う",
+  chunking: "長時間録音の分割",
+  chunking_desc: "上限を超える録音を無音部分で分割し、文字起こし用にアップロード",
+  chunking_max_minutes: "チャンク最大長（分）",
+  chunking_threshold_db: "無音しきい値（dB）",
+  chunking_min_silence_ms: "最小無音長（ms）",
+  native_encoding: "内蔵エンコーダー",
+  native_encoding_desc: "ffmpeg が使えない場合に WAV ファイルの変換に使用します。WMA ファイルには引き続き ffmpeg が必要です。",
+  native_encoding_compact: "コンパクト（MP3、16 kHz モノラル）",
+  native_encoding_lossless: "ロスレス（FLAC、元の音質）",
+  conversion_profile: "変換プロファイル",
+  conversion_profile_desc: "コーデック、ビットレート、サンプルレート、チャンネル数と変換対象のファイル。プロファイルは config.json で定義します。",
+  channel_mode: "チャンネルの扱い",
+  channel_mode_desc: "選択中のプロファイルに適用します。「分割」では、マルチチャンネル録音（左右それぞれにピンマイクなど）の各チャンネルを、関連付けられた個別のモノラル録音にします。",
+  channel_mode_keep: "そのまま（ステレオはステレオのまま）",
+  channel_mode_downmix: "ダウンミックス",
+  channel_mode_split: "チャンネルごとに分割",
+  device_profiles: "デバイス別プロファイル",
+  device_profiles_desc: "ここに登録したレコーダーは既定の代わりに指定したプロファイルを使用します",
+  device_id: "デバイス ID",
+  add_device_profile: "デバイスを追加",
+  screening: "空の録音を検出",
+  screening_desc: "各録音に含まれる発話の割合を測定し、空の可能性が高い録音に印を付けます",
+  screening_threshold_db: "発話レベル（dB）",
+  screening_min_ratio: "最小発話割合（0〜1）",
+  screening_min_segment_ms: "最小連続発話長（ms）",
+  screening_hold: "空の可能性がある録音を保留",
+  screening_hold_desc: "印が付いた録音は、バッチページでアップロードを確認するまで inbox に保留します",
+  generate_waveforms: "波形",
+  generate_waveforms_desc: "取り込んだ録音の波形を作成し、inbox にキャッシュします",
+  upload_waveforms: "波形をアップロード",
+  upload_waveforms_desc: "録音ページで表示できるよう、波形をサーバーへ送信します",
+  retry_attempts: "アップロード試行回数",
+  retry_initial_delay_ms: "最初の再試行まで (ms)",
+  retry_max_delay_ms: "最大待機時間 (ms)",
+  retry_desc: "接続の切断・サーバーエラー・レート制限は、待機時間を延ばしながら再試行します",
+  multipart_adaptive: "マルチパートアップロードの自動調整",
+  multipart_adaptive_desc: "大きなファイルほどパートを大きくし、計測した速度とエラーに合わせて並列数を調整します",
+  multipart_threshold_mb: "分割するファイルサイズ (MB)",
+  multipart_part_size_mb: "パートサイズ (MB)",
+  multipart_min_part_size_mb: "最小パートサイズ (MB)",
+  multipart_concurrency: "並列パート数",
+  multipart_start_concurrency: "並列パート数の初期値",
+  multipart_max_concurrency: "並列パート数の上限",
+  bandwidth_limit_mbps: "アップロード速度の上限 (Mbps)",
+  bandwidth_limit_desc: "すべてのアップロードの合計に適用されます。0 で無制限",
+  bandwidth_schedule: "時間帯ごとの上限",
+  bandwidth_schedule_desc: "指定した時間帯 (ローカル時刻) は上の上限の代わりに適用されます。終了が開始より前の時間帯は日付をまたぎます",
+  bandwidth_window_start: "開始",
+  bandwidth_window_end: "終了",
+  bandwidth_add_window: "時間帯を追加",
+  upload_bandwidth: "アップロード帯域",
+  bandwidth_unlimited: "無制限",
+  bandwidth_current: "現在の上限",
+  bandwidth_overridden: "解除するまでここで設定した値を使用",
+  bandwidth_throttle: "制限する",
+  bandwidth_unthrottle: "制限を外す",
+  bandwidth_use_settings: "設定に戻す",
+  stale_multipart_hours: "未完了アップロードの破棄まで (時間)",
+  stale_multipart_hours_desc: "中断した大きなアップロードはこの時間内なら再開できます。過ぎると保存済みのパートを削除します",
+  upload_concurrency: "同時アップロード数",
+  upload_concurrency_desc: "すべての取り込みと手動アップロードで共有します",
+  http_connect_timeout_secs: "接続タイムアウト (秒)",
+  http_read_timeout_secs: "停止検出タイムアウト (秒)",
+  http_pool_idle_secs: "アイドル接続の保持 (秒)",
+  http_keepalive_secs: "TCP キープアライブ (秒)",
+  http_timeouts_desc: "この時間まったく進まない通信は失敗として再試行します。キープアライブは 0 で無効になります",
+  http2: "HTTP/2",
+  http2_desc: "対応するサーバーとは HTTP/2 を使い、1 つの接続で多くのリクエストを送ります",
+  http_proxy_url: "プロキシ",
+  http_proxy_url_desc: "サーバーとストレージへのアップロードの両方に使います。空欄ならシステムのプロキシを使います。認証が必要な場合は URL に含めてください",
+  http_no_proxy: "プロキシを使わないホスト",
+  http_no_proxy_desc: "直接接続するホストをカンマ区切りで指定します。名前はサブドメインも含み、IP の範囲は CIDR で指定できます",
+  http_ca_certificates: "追加で信頼する CA 証明書",
+  http_ca_certificates_desc: "システムの証明書に加えて信頼する PEM ファイルのパスを 1 行に 1 つ指定します (HTTPS を検査するプロキシのルート証明書など)",
+  connection_via_proxy: "設定したプロキシ経由で接続しました",
+  connection_problem_invalidSettings: "プロキシ URL または CA 証明書ファイルを使用できません。パスと、ファイルが PEM 形式の証明書であることを確認してください。",
+  connection_problem_dns: "サーバー名が見つかりません。サーバー URL とネットワークまたは DNS の設定を確認してください。",
+  connection_problem_proxy: "プロキシが接続を拒否したか、認証を求めています。プロキシ URL を確認し、必要であればユーザー名とパスワードを含めてください。",
+  connection_problem_untrustedCertificate: "サーバーの証明書が、このコンピューターが信頼していない認証局で署名されています。HTTPS 通信を検査するネットワークで起こります。IT 部門からルート証明書を入手し、「追加で信頼する CA 証明書」に追加してください。",
+  connection_problem_certificateHostMismatch: "サーバーの証明書が別のホスト名のものです。サーバー URL が証明書の名前と一致しているか確認してください。",
+  connection_problem_certificateExpired: "サーバーの証明書が期限切れか、まだ有効になっていません。このコンピューターの時刻を確認し、正しければ証明書の更新が必要です。",
+  connection_problem_tls: "安全な接続を確立できませんでした。プロキシやファイアウォールが HTTPS を妨げている可能性があります。",
+  connection_problem_timeout: "サーバーが時間内に応答しませんでした。",
+  connection_problem_unreachable: "サーバーに接続できません。サーバー URL とネットワーク接続を確認してください。",
+  upload_queue: "アップロード待ち",
+  queue_waiting: "待機中",
+  queue_paused: "一時停止",
+  queue_uploading: "アップロード中",
+  priority_low: "低",
+  priority_normal: "通常",
+  priority_high: "高",
+  queue_move_up: "上へ",
+  queue_move_down: "下へ",
+  queue_pause: "一時停止",
+  queue_resume: "再開",
+  queue_cancel: "キャンセル",
+  offline_backlog: "ネットワーク待ち",
+  offline_backlog_batches: "バッチ数",
+  offline_unreachable: "サーバーに接続できません。30 秒ごとに確認しています",
+  offline_reachable: "サーバーに接続できました。アップロードを再開しています",
+  offline_checked_at: "最終確認",
   saving: "保存中...",
   save: "保存",
   saved: "保存しました",
@@ -87,14 +196,28 @@ export const ja: Record<TranslationKey, string> = {
   // FileList
   uploading: "アップロード中",
   no_files: "ファイルなし",
+  held: "確認待ち",
+  discarded: "破棄済み",
+  waiting_for_network: "ネットワーク待ち",
+  likely_empty: "空の可能性",
+  speech: "発話",
+  upload_anyway: "アップロード",
+  discard: "破棄",
+  channel: "チャンネル",
 
   // ImportLog phases
   phase_scanning: "ファイルスキャン中",
   phase_copying: "コピー中",
+  phase_validating: "検証中",
+  phase_stitching: "分割録音を結合中",
   phase_converting: "変換中",
+  phase_probing: "音声を解析中",
+  phase_screening: "発話を確認中",
+  phase_splitting: "無音で分割中",
   phase_hashing: "ハッシュ計算中",
   phase_preflight: "プリフライト確認中",
   phase_uploading: "アップロード中",
+  phase_waiting_for_network: "ネットワーク待ち。サーバーに接続できるとアップロードを開始します",
   phase_completing: "完了処理中",
   phase_done: "完了",
   phase_error: "エラー",
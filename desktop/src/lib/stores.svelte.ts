@@ -1,4 +1,4 @@
-import type { MountInfo, BatchState, ImportProgress } from "./types";
+import type { MountInfo, BatchState, ImportProgress, ConversionProgress } from "./types";
 import * as commands from "./tauri";
 import type { UnlistenFn } from "@tauri-apps/api/event";
 
@@ -9,6 +9,7 @@ function createAppStore() {
   let serverConnected = $state<boolean>(false);
   let currentImportBatchId = $state<string | null>(null);
   let importProgress = $state<ImportProgress | null>(null);
+  let conversionProgress = $state<ConversionProgress | null>(null);
   let unlisteners: UnlistenFn[] = [];
 
   return {
@@ -22,6 +23,7 @@ function createAppStore() {
     set currentImportBatchId(v: string | null) { currentImportBatchId = v; },
     get importProgress() { return importProgress; },
     set importProgress(v: ImportProgress | null) { importProgress = v; },
+    get conversionProgress() { return conversionProgress; },
 
     async init() {
       try {
@@ -61,6 +63,15 @@ function createAppStore() {
         await commands.onImportProgress((progress) => {
           importProgress = progress;
           currentImportBatchId = progress.batchId;
+          if (progress.phase !== "converting") {
+            conversionProgress = null;
+          }
+        }),
+      );
+
+      unlisteners.push(
+        await commands.onConversionProgress((progress) => {
+          conversionProgress = progress;
         }),
       );
     },
@@ -10,6 +10,14 @@ import type {
   HashProgress,
   UploadProgress,
   ImportProgress,
+  ConversionProgress,
+  AudioProbe,
+  WaveformPeaks,
+  BandwidthStatus,
+  QueuedUpload,
+  UploadPriority,
+  OfflineBacklog,
+  ConnectionCheck,
 } from "./types";
 
 // ===== Commands =====
@@ -42,6 +50,50 @@ export async function uploadFiles(files: ManualUploadFile[]): Promise<string> {
   return invoke<string>("upload_files", { files });
 }
 
+export async function resolveHeldFile(batchId: string, sha256: string, upload: boolean): Promise<void> {
+  return invoke<void>("resolve_held_file", { batchId, sha256, upload });
+}
+
+export async function getWaveform(path: string): Promise<WaveformPeaks> {
+  return invoke<WaveformPeaks>("get_waveform", { path });
+}
+
+export async function getBandwidthStatus(): Promise<BandwidthStatus> {
+  return invoke<BandwidthStatus>("get_bandwidth_status");
+}
+
+export async function setBandwidthLimit(limitMbps: number | null): Promise<BandwidthStatus> {
+  return invoke<BandwidthStatus>("set_bandwidth_limit", { limitMbps });
+}
+
+export async function getUploadQueue(): Promise<QueuedUpload[]> {
+  return invoke<QueuedUpload[]>("get_upload_queue");
+}
+
+export async function setUploadPriority(id: number, priority: UploadPriority): Promise<void> {
+  return invoke<void>("set_upload_priority", { id, priority });
+}
+
+export async function moveUpload(id: number, position: number): Promise<void> {
+  return invoke<void>("move_upload", { id, position });
+}
+
+export async function setUploadPaused(id: number, paused: boolean): Promise<void> {
+  return invoke<void>("set_upload_paused", { id, paused });
+}
+
+export async function cancelUpload(id: number): Promise<void> {
+  return invoke<void>("cancel_upload", { id });
+}
+
+export async function getOfflineBacklog(): Promise<OfflineBacklog> {
+  return invoke<OfflineBacklog>("get_offline_backlog");
+}
+
+export async function checkConnection(): Promise<ConnectionCheck> {
+  return invoke<ConnectionCheck>("check_connection");
+}
+
 export async function getConfig(): Promise<AppConfig> {
   return invoke<AppConfig>("get_config");
 }
@@ -66,6 +118,10 @@ export async function detectFfmpegPath(): Promise<string | null> {
   return invoke<string | null>("detect_ffmpeg_path");
 }
 
+export async function probeAudio(path: string, ffmpegPath?: string): Promise<AudioProbe> {
+  return invoke<AudioProbe>("probe_audio", { path, ffmpegPath: ffmpegPath ?? null });
+}
+
 export async function getBatches(): Promise<Record<string, BatchState>> {
   return invoke<Record<string, BatchState>>("get_batches");
 }
@@ -88,6 +144,10 @@ export function onImportProgress(cb: (progress: ImportProgress) => void): Promis
   return listen<ImportProgress>("import-progress", (event) => cb(event.payload));
 }
 
+export function onConversionProgress(cb: (progress: ConversionProgress) => void): Promise<UnlistenFn> {
+  return listen<ConversionProgress>("conversion-progress", (event) => cb(event.payload));
+}
+
 export function onHashProgress(cb: (progress: HashProgress) => void): Promise<UnlistenFn> {
   return listen<HashProgress>("hash-progress", (event) => cb(event.payload));
 }
@@ -95,3 +155,11 @@ export function onHashProgress(cb: (progress: HashProgress) => void): Promise<Un
 export function onUploadProgress(cb: (progress: UploadProgress) => void): Promise<UnlistenFn> {
   return listen<UploadProgress>("upload-progress", (event) => cb(event.payload));
 }
+
+export function onOfflineBacklog(cb: (backlog: OfflineBacklog) => void): Promise<UnlistenFn> {
+  return listen<OfflineBacklog>("offline-backlog", (event) => cb(event.payload));
+}
+
+export function onUploadQueue(cb: (queue: QueuedUpload[]) => void): Promise<UnlistenFn> {
+  return listen<QueuedUpload[]>("upload-queue", (event) => cb(event.payload));
+}
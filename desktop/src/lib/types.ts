@@ -5,10 +5,14 @@ export interface MountInfo {
 }
 
 export interface RecorderIdentifier {
+  version: number;
   deviceId: string;
   label: string;
   orgIdHint?: string;
   notes?: string;
+  model?: string;
+  timezone?: string;
+  clockOffsetSeconds: number;
 }
 
 export interface AppConfig {
@@ -18,9 +22,168 @@ export interface AppConfig {
   autoImport: boolean;
   autoStart: boolean;
   watchIntervalMs: number;
+  splitSequences: SequenceMode;
+  chunking: ChunkingConfig;
+  nativeEncoding: NativeEncoding;
+  conversionProfiles: ConversionProfile[];
+  conversionProfile: string;
+  deviceProfiles: Record<string, string>;
+  screening: ScreeningConfig;
+  generateWaveforms: boolean;
+  uploadWaveforms: boolean;
+  retry: RetryConfig;
+  multipart: MultipartConfig;
+  bandwidth: BandwidthConfig;
+  staleMultipartHours: number;
+  uploadConcurrency: number;
+  http: HttpConfig;
 }
 
-export type BatchStatus = "OPEN" | "UPLOADING" | "COMPLETED" | "PARTIAL_ERROR";
+export interface ConversionProfile {
+  name: string;
+  codec: string;
+  container: string;
+  bitrateKbps?: number;
+  sampleRate?: number;
+  channels?: number;
+  channelMode?: ChannelMode;
+  filters: string[];
+  triggers: ConversionTrigger[];
+  preprocess: PreprocessConfig;
+}
+
+export type ChannelMode = "keep" | "downmix" | "split";
+
+export interface PreprocessConfig {
+  highpassHz?: number;
+  humHz?: number;
+  gateThresholdDb?: number;
+  loudnessTargetLufs?: number;
+  truePeakDbtp: number;
+  loudnessRangeLu: number;
+}
+
+export interface LoudnessStats {
+  integratedLufs: number;
+  truePeakDbtp: number;
+  loudnessRangeLu: number;
+  thresholdLufs: number;
+  targetOffsetLu: number;
+  targetLufs: number;
+}
+
+export interface ConversionTrigger {
+  format: string;
+  minSizeMb: number;
+}
+
+export interface ChunkingConfig {
+  enabled: boolean;
+  maxChunkMinutes: number;
+  overlapMs: number;
+  silenceThresholdDb: number;
+  minSilenceMs: number;
+}
+
+export interface RetryConfig {
+  maxAttempts: number;
+  initialDelayMs: number;
+  maxDelayMs: number;
+}
+
+export interface MultipartConfig {
+  thresholdMb: number;
+  partSizeMb: number;
+  concurrency: number;
+  adaptive: boolean;
+  maxConcurrency: number;
+}
+
+export interface HttpConfig {
+  connectTimeoutSecs: number;
+  readTimeoutSecs: number;
+  poolIdleSecs: number;
+  keepaliveSecs: number;
+  http2: boolean;
+  proxyUrl?: string | null;
+  noProxy: string[];
+  caCertificates: string[];
+}
+
+export type ConnectionProblem =
+  | "invalidSettings"
+  | "dns"
+  | "proxy"
+  | "untrustedCertificate"
+  | "certificateHostMismatch"
+  | "certificateExpired"
+  | "tls"
+  | "timeout"
+  | "unreachable";
+
+export interface ConnectionCheck {
+  ok: boolean;
+  status?: number;
+  problem?: ConnectionProblem;
+  detail?: string;
+  viaProxy: boolean;
+}
+
+export interface BandwidthConfig {
+  limitMbps: number;
+  schedule: BandwidthWindow[];
+}
+
+export interface BandwidthWindow {
+  start: string;
+  end: string;
+  limitMbps: number;
+}
+
+export interface BandwidthStatus {
+  limitMbps: number;
+  overrideMbps?: number;
+}
+
+export interface OfflineBacklog {
+  batches: number;
+  files: number;
+  bytes: number;
+  online?: boolean;
+  checkedAt?: string;
+}
+
+export type UploadPriority = "low" | "normal" | "high";
+
+export interface QueuedUpload {
+  id: number;
+  batchId: string;
+  sha256: string;
+  fileName: string;
+  sizeBytes: number;
+  priority: UploadPriority;
+  status: "waiting" | "paused" | "uploading";
+}
+
+export interface ScreeningConfig {
+  enabled: boolean;
+  speechThresholdDb: number;
+  minSpeechRatio: number;
+  minSpeechSegmentMs: number;
+  holdEmpty: boolean;
+}
+
+export type SequenceMode = "group" | "stitch" | "off";
+
+export type NativeEncoding = "compact" | "lossless";
+
+export type BatchStatus =
+  | "OPEN"
+  | "UPLOADING"
+  | "COMPLETED"
+  | "PARTIAL_ERROR"
+  | "AWAITING_REVIEW"
+  | "WAITING_FOR_NETWORK";
 
 export interface BatchState {
   status: BatchStatus;
@@ -28,16 +191,102 @@ export interface BatchState {
   files: Record<string, FileStatus>;
 }
 
+export interface CompletedPart {
+  partNumber: number;
+  eTag: string;
+  checksumSha256?: string;
+}
+
 export interface FileStatus {
   recordingId: string;
   uploaded: boolean;
+  verified: boolean;
   error?: string;
   uploadId?: string;
   rawR2Key?: string;
-  completedParts?: number[];
+  completedParts?: CompletedPart[];
   multipartUploadId?: string;
+  multipartStartedAt?: string;
+  multipartPartSize?: number;
+  recordedAt?: string;
+  recordedAtSource?: TimestampSource;
+  originalSha256?: string;
+  repairedSha256?: string;
+  uploadSha256?: string;
+  storedSha256?: string;
+  audio?: AudioProbe;
+  sequence?: SequenceInfo;
+  channel?: ChannelInfo;
+  stitchedFrom?: string[];
+  chunks?: ChunkInfo[];
+  conversionProfile?: string;
+  loudness?: LoudnessStats;
+  localPath?: string;
+  voiceActivity?: VoiceActivity;
+  held?: HeldUpload;
+  discarded: boolean;
+  waitingForNetwork?: HeldUpload;
+}
+
+export interface WaveformPeaks {
+  version: number;
+  sampleRate: number;
+  samplesPerPeak: number;
+  durationMs: number;
+  /** Interleaved min/max pairs scaled to -127..127 */
+  peaks: number[];
+}
+
+export interface VoiceActivity {
+  durationMs: number;
+  speechMs: number;
+  speechRatio: number;
+  longestSpeechMs: number;
+  likelyEmpty: boolean;
+}
+
+export interface HeldUpload {
+  localPath: string;
+  originalFileName: string;
+  sizeBytes: number;
+  recordSizeBytes?: number;
 }
 
+export interface ChunkInfo {
+  index: number;
+  startMs: number;
+  endMs: number;
+  path: string;
+}
+
+export interface SequenceInfo {
+  sequenceId: string;
+  sequenceIndex: number;
+  sequenceCount: number;
+}
+
+export interface ChannelInfo {
+  channelGroupId: string;
+  channelIndex: number;
+  channelCount: number;
+}
+
+export interface AudioProbe {
+  mimeType: string;
+  durationMs?: number;
+  codec?: string;
+  sampleRate?: number;
+  channels?: number;
+}
+
+export type TimestampSource =
+  | "bext"
+  | "riffInfo"
+  | "id3"
+  | "mp4"
+  | "fileName"
+  | "fileModified";
+
 export interface ImportProgress {
   batchId: string;
   phase: string;
@@ -47,6 +296,15 @@ export interface ImportProgress {
   message?: string;
 }
 
+export interface ConversionProgress {
+  batchId: string;
+  fileName: string;
+  outTimeMs: number;
+  percent?: number;
+  speed?: number;
+  etaSeconds?: number;
+}
+
 export interface HashProgress {
   fileName: string;
   bytesHashed: number;
@@ -60,6 +318,10 @@ export interface UploadProgress {
   totalBytes: number;
   partNumber?: number;
   totalParts?: number;
+  /** Set on entries announcing a retry: the attempt about to start */
+  attempt?: number;
+  retryDelayMs?: number;
+  error?: string;
 }
 
 export interface FileInfo {
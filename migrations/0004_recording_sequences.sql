@@ -0,0 +1,8 @@
+-- Migration: 0004_recording_sequences
+-- Description: Link recordings that a recorder split into several files (e.g. REC001_01.WAV, REC001_02.WAV)
+
+ALTER TABLE recordings ADD COLUMN sequenceId TEXT;
+ALTER TABLE recordings ADD COLUMN sequenceIndex INTEGER;
+ALTER TABLE recordings ADD COLUMN sequenceCount INTEGER;
+
+CREATE INDEX IF NOT EXISTS idx_recordings_org_sequence ON recordings(orgId, sequenceId);
@@ -0,0 +1,8 @@
+-- Migration: 0005_recording_channels
+-- Description: Link the mono recordings split from one multi-channel source (e.g. a lapel mic on each stereo channel)
+
+ALTER TABLE recordings ADD COLUMN channelGroupId TEXT;
+ALTER TABLE recordings ADD COLUMN channelIndex INTEGER;
+ALTER TABLE recordings ADD COLUMN channelCount INTEGER;
+
+CREATE INDEX IF NOT EXISTS idx_recordings_org_channel_group ON recordings(orgId, channelGroupId);
@@ -0,0 +1,7 @@
+-- Migration: 0006_upload_verification
+-- Description: Record the size and SHA-256 of the raw object in storage when an upload is completed, so the uploader can verify it.
+-- Multipart objects are verified against a composite of their part checksums, kept when the upload is completed.
+
+ALTER TABLE recordings ADD COLUMN storedSizeBytes INTEGER;
+ALTER TABLE recordings ADD COLUMN storedSha256 TEXT;
+ALTER TABLE recordings ADD COLUMN multipartSha256 TEXT;
@@ -180,6 +180,19 @@ export function getRecordingPresignUrl(id: string) {
   return fetchApi<{ url: string; expiresAt: string }>(`/recordings/${id}/presign`);
 }
 
+export interface WaveformPeaks {
+  version: number;
+  sampleRate: number;
+  samplesPerPeak: number;
+  durationMs: number;
+  /** Interleaved min/max pairs scaled to -127..127 */
+  peaks: number[];
+}
+
+export function getRecordingWaveform(id: string) {
+  return fetchApi<WaveformPeaks>(`/recordings/${id}/waveform`);
+}
+
 // ----- Workshops -----
 
 interface WorkshopListParams {
@@ -68,6 +68,12 @@ export const MULTIPART = {
   MAX_CONCURRENT: 4,
 } as const;
 
+// ===== Upload Verification =====
+export const UPLOAD_VERIFICATION = {
+  // Larger objects without a stored checksum are not read back to hash
+  STREAM_HASH_MAX_BYTES: 100 * 1024 * 1024, // 100MB
+} as const;
+
 // ===== R2 Key Patterns =====
 export const R2_KEYS = {
   raw: (orgId: string, recordingId: string, fileName: string) =>
@@ -81,6 +87,8 @@ export const R2_KEYS = {
     ext: string,
   ) =>
     `org/${orgId}/recording/${recordingId}/chunks/${chunkIndex}_${startMs}_${endMs}.${ext}`,
+  waveform: (orgId: string, recordingId: string) =>
+    `org/${orgId}/recording/${recordingId}/waveform.json`,
   artifact: (
     orgId: string,
     recordingId: string,
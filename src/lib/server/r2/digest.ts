@@ -0,0 +1,75 @@
+import { UPLOAD_VERIFICATION } from "$lib/constants.js";
+
+/**
+ * SHA-256 of an object in R2, as lowercase hex, or null when it can't be
+ * worked out within the Worker's limits.
+ *
+ * Uses the checksum R2 kept from a checksummed PUT when there is one. Objects
+ * assembled from multipart uploads only carry per-part checksums, so
+ * `multipartSha256`, the composite recorded when the upload was completed,
+ * stands in for them. Anything else is read back and hashed as a stream, but
+ * only when small.
+ */
+export async function objectSha256(
+  bucket: R2Bucket,
+  key: string,
+  object: R2Object,
+  multipartSha256: string | null,
+): Promise<string | null> {
+  const stored = object.checksums.sha256;
+  if (stored) {
+    return toHex(stored);
+  }
+  if (multipartSha256) {
+    return multipartSha256;
+  }
+  if (object.size > UPLOAD_VERIFICATION.STREAM_HASH_MAX_BYTES) {
+    return null;
+  }
+
+  const body = await bucket.get(key);
+  if (!body) {
+    return null;
+  }
+  const digest = new crypto.DigestStream("SHA-256");
+  await body.body.pipeTo(digest);
+  return toHex(await digest.digest);
+}
+
+/**
+ * Composite checksum of a multipart upload: the SHA-256 of its parts' SHA-256
+ * digests concatenated in part order, as hex with the part count appended
+ * (`<hex>-<parts>`), the way S3 composes them. Null unless every part carries
+ * a base64 SHA-256, since storage has only checked the parts that did.
+ */
+export async function compositeSha256(
+  parts: { partNumber: number; checksumSha256?: string }[],
+): Promise<string | null> {
+  const digests: Uint8Array[] = [];
+  for (const part of [...parts].sort((a, b) => a.partNumber - b.partNumber)) {
+    const digest = part.checksumSha256 ? fromBase64(part.checksumSha256) : null;
+    if (!digest || digest.length !== 32) {
+      return null;
+    }
+    digests.push(digest);
+  }
+
+  const joined = new Uint8Array(digests.length * 32);
+  digests.forEach((digest, i) => joined.set(digest, i * 32));
+  const hash = await crypto.subtle.digest("SHA-256", joined);
+  return `${toHex(hash)}-${digests.length}`;
+}
+
+export function toHex(bytes: ArrayBuffer): string {
+  return [...new Uint8Array(bytes)]
+    .map((b) => b.toString(16).padStart(2, "0"))
+    .join("");
+}
+
+function fromBase64(value: string): Uint8Array | null {
+  try {
+    return Uint8Array.from(atob(value), (c) => c.charCodeAt(0));
+  } catch {
+    return null;
+  }
+}
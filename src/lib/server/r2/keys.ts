@@ -24,6 +24,10 @@ export function chunkKey(
   return R2_KEYS.chunk(orgId, recordingId, chunkIndex, startMs, endMs, ext);
 }
 
+export function waveformKey(orgId: string, recordingId: string): string {
+  return R2_KEYS.waveform(orgId, recordingId);
+}
+
 export function artifactKey(
   orgId: string,
   recordingId: string,
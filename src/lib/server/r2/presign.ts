@@ -1,5 +1,6 @@
 import {
   S3Client,
+  AbortMultipartUploadCommand,
   CreateMultipartUploadCommand,
   CompleteMultipartUploadCommand,
   ListPartsCommand,
@@ -21,23 +22,37 @@ export function createR2Client(env: Env): S3Client {
   });
 }
 
+/**
+ * Checksum headers are signed but kept out of the query string, so the
+ * uploader has to send them and R2 rejects a body that does not match.
+ */
+const CHECKSUM_HEADER = "x-amz-checksum-sha256";
+
 export async function presignPutUrl(
   client: S3Client,
   bucket: string,
   key: string,
   expiresIn: number,
   contentType?: string,
+  checksumSha256?: string,
 ): Promise<{ url: string; headers: Record<string, string> }> {
   const command = new PutObjectCommand({
     Bucket: bucket,
     Key: key,
     ...(contentType ? { ContentType: contentType } : {}),
+    ...(checksumSha256 ? { ChecksumSHA256: checksumSha256 } : {}),
+  });
+  const url = await getSignedUrl(client, command, {
+    expiresIn,
+    unhoistableHeaders: new Set([CHECKSUM_HEADER]),
   });
-  const url = await getSignedUrl(client, command, { expiresIn });
   const headers: Record<string, string> = {};
   if (contentType) {
     headers["Content-Type"] = contentType;
   }
+  if (checksumSha256) {
+    headers[CHECKSUM_HEADER] = checksumSha256;
+  }
   return { url, headers };
 }
 
@@ -59,11 +74,13 @@ export async function createMultipartUpload(
   bucket: string,
   key: string,
   contentType?: string,
+  checksumAlgorithm?: "SHA256",
 ): Promise<{ uploadId: string }> {
   const command = new CreateMultipartUploadCommand({
     Bucket: bucket,
     Key: key,
     ...(contentType ? { ContentType: contentType } : {}),
+    ...(checksumAlgorithm ? { ChecksumAlgorithm: checksumAlgorithm } : {}),
   });
   const response = await client.send(command);
   if (!response.UploadId) {
@@ -79,14 +96,24 @@ export async function presignPartUrl(
   uploadId: string,
   partNumber: number,
   expiresIn: number,
-): Promise<string> {
+  checksumSha256?: string,
+): Promise<{ url: string; headers: Record<string, string> }> {
   const command = new UploadPartCommand({
     Bucket: bucket,
     Key: key,
     UploadId: uploadId,
     PartNumber: partNumber,
+    ...(checksumSha256 ? { ChecksumSHA256: checksumSha256 } : {}),
   });
-  return getSignedUrl(client, command, { expiresIn });
+  const url = await getSignedUrl(client, command, {
+    expiresIn,
+    unhoistableHeaders: new Set([CHECKSUM_HEADER]),
+  });
+  const headers: Record<string, string> = {};
+  if (checksumSha256) {
+    headers[CHECKSUM_HEADER] = checksumSha256;
+  }
+  return { url, headers };
 }
 
 export async function completeMultipartUpload(
@@ -94,7 +121,7 @@ export async function completeMultipartUpload(
   bucket: string,
   key: string,
   uploadId: string,
-  parts: { partNumber: number; eTag: string }[],
+  parts: { partNumber: number; eTag: string; checksumSha256?: string }[],
 ): Promise<void> {
   const command = new CompleteMultipartUploadCommand({
     Bucket: bucket,
@@ -104,27 +131,73 @@ export async function completeMultipartUpload(
       Parts: parts.map((p) => ({
         PartNumber: p.partNumber,
         ETag: p.eTag,
+        ...(p.checksumSha256 ? { ChecksumSHA256: p.checksumSha256 } : {}),
       })),
     },
   });
   await client.send(command);
 }
 
-export async function listParts(
+/**
+ * Discard a multipart upload and the parts stored for it. An upload that no
+ * longer exists counts as aborted.
+ */
+export async function abortMultipartUpload(
   client: S3Client,
   bucket: string,
   key: string,
   uploadId: string,
-): Promise<{ partNumber: number; eTag: string; size: number }[]> {
-  const command = new ListPartsCommand({
+): Promise<void> {
+  const command = new AbortMultipartUploadCommand({
     Bucket: bucket,
     Key: key,
     UploadId: uploadId,
   });
-  const response = await client.send(command);
-  return (response.Parts ?? []).map((p) => ({
-    partNumber: p.PartNumber!,
-    eTag: p.ETag!,
-    size: p.Size!,
-  }));
+  try {
+    await client.send(command);
+  } catch (err) {
+    if (!(err instanceof Error && err.name === "NoSuchUpload")) {
+      throw err;
+    }
+  }
+}
+
+/**
+ * Parts stored so far for a multipart upload, following the listing past
+ * S3's page of 1000 parts.
+ */
+export async function listParts(
+  client: S3Client,
+  bucket: string,
+  key: string,
+  uploadId: string,
+): Promise<
+  { partNumber: number; eTag: string; size: number; checksumSha256?: string }[]
+> {
+  const parts: {
+    partNumber: number;
+    eTag: string;
+    size: number;
+    checksumSha256?: string;
+  }[] = [];
+  let marker: string | undefined;
+  do {
+    const command = new ListPartsCommand({
+      Bucket: bucket,
+      Key: key,
+      UploadId: uploadId,
+      ...(marker ? { PartNumberMarker: marker } : {}),
+    });
+    const response = await client.send(command);
+    for (const p of response.Parts ?? []) {
+      parts.push({
+        partNumber: p.PartNumber!,
+        eTag: p.ETag!,
+        size: p.Size!,
+        ...(p.ChecksumSHA256 ? { checksumSha256: p.ChecksumSHA256 } : {}),
+      });
+    }
+    marker = response.IsTruncated ? response.NextPartNumberMarker : undefined;
+  } while (marker);
+  return parts;
 }
@@ -131,6 +131,18 @@ export interface Recording {
   needsConversion: number; // D1 boolean (0/1)
   convertedR2Key: string | null;
   rawR2Key: string;
+  sequenceId: string | null;
+  sequenceIndex: number | null;
+  sequenceCount: number | null;
+  channelGroupId: string | null;
+  channelIndex: number | null;
+  channelCount: number | null;
+  /** Size of the raw object in storage, recorded when the upload is completed */
+  storedSizeBytes: number | null;
+  /** SHA-256 (hex) of the raw object in storage, recorded with its size */
+  storedSha256: string | null;
+  /** Composite of the part checksums of a completed multipart upload */
+  multipartSha256: string | null;
   status: RecordingStatus;
   createdAt: string;
   updatedAt: string;
@@ -229,6 +241,14 @@ export interface PreflightRequest {
   recorderFileCreatedAt?: string;
   sizeBytes: number;
   sha256: string;
+  mimeType?: string;
+  durationMs?: number;
+  sequenceId?: string;
+  sequenceIndex?: number;
+  sequenceCount?: number;
+  channelGroupId?: string;
+  channelIndex?: number;
+  channelCount?: number;
 }
 
 export interface PreflightResult {
@@ -50,3 +50,13 @@ function hexEncode(bytes: Uint8Array): string {
     .map((b) => b.toString(16).padStart(2, "0"))
     .join("");
 }
+
+/**
+ * Re-encode a hex SHA-256 digest as base64, the form used by
+ * `x-amz-checksum-sha256`. Returns null for anything that is not a digest.
+ */
+export function hexToBase64(hex: string): string | null {
+  if (!/^[0-9a-fA-F]{64}$/.test(hex)) return null;
+  const bytes = hex.match(/../g)!.map((b) => parseInt(b, 16));
+  return btoa(String.fromCharCode(...bytes));
+}
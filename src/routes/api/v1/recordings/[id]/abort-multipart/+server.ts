@@ -0,0 +1,54 @@
+import {
+  withAuth,
+  validateBody,
+  requireString,
+} from "$lib/server/api/middleware.js";
+import { HttpError, jsonResponse } from "$lib/utils/response.js";
+import {
+  createR2Client,
+  abortMultipartUpload,
+} from "$lib/server/r2/presign.js";
+import type { RequestHandler } from "./$types.js";
+
+/**
+ * Abort a multipart upload the client has given up on, so its parts stop
+ * taking up storage. The recording goes back to REGISTERED, which lets a
+ * later preflight replace it.
+ */
+export const POST: RequestHandler = withAuth(
+  async ({ request, platform, org, params }) => {
+    const env = platform.env;
+    const recordingId = params.id;
+
+    const recording = await env.DB.prepare(
+      "SELECT id, rawR2Key FROM recordings WHERE id = ? AND orgId = ?",
+    )
+      .bind(recordingId, org.orgId)
+      .first<{ id: string; rawR2Key: string }>();
+
+    if (!recording) {
+      throw new HttpError(404, "NOT_FOUND", "Recording not found");
+    }
+
+    const body = await validateBody(request, (b) => {
+      const obj = b as Record<string, unknown>;
+      return { uploadId: requireString(obj, "uploadId") };
+    });
+
+    const client = createR2Client(env);
+    await abortMultipartUpload(
+      client,
+      "r2-raw-audio",
+      recording.rawR2Key,
+      body.uploadId,
+    );
+
+    await env.DB.prepare(
+      "UPDATE recordings SET status = 'REGISTERED', updatedAt = datetime('now') WHERE id = ? AND orgId = ? AND status = 'UPLOADING'",
+    )
+      .bind(recordingId, org.orgId)
+      .run();
+
+    return jsonResponse({ ok: true });
+  },
+) as RequestHandler;
@@ -0,0 +1,99 @@
+import {
+  withAuth,
+  validateBody,
+  requireString,
+  requireNumber,
+} from "$lib/server/api/middleware.js";
+import { HttpError, jsonResponse } from "$lib/utils/response.js";
+import { generateId } from "$lib/utils/id.js";
+import { ID_PREFIX, PRESIGN_TTL, R2_KEYS } from "$lib/constants.js";
+import { createR2Client, presignPutUrl } from "$lib/server/r2/presign.js";
+import { hexToBase64 } from "$lib/utils/hash.js";
+import type { RequestHandler } from "./$types.js";
+
+/**
+ * Register a silence-cut chunk produced by the importer and presign its upload.
+ * Chunks must be uploaded before the raw file, whose upload starts processing.
+ * Registering an index again replaces the previous chunk.
+ */
+export const POST: RequestHandler = withAuth(
+  async ({ request, platform, org, params }) => {
+    const env = platform.env;
+    const recordingId = params.id;
+
+    const recording = await env.DB.prepare(
+      "SELECT id, status, mimeType FROM recordings WHERE id = ? AND orgId = ?",
+    )
+      .bind(recordingId, org.orgId)
+      .first<{ id: string; status: string; mimeType: string }>();
+
+    if (!recording) {
+      throw new HttpError(404, "NOT_FOUND", "Recording not found");
+    }
+
+    if (recording.status !== "REGISTERED" && recording.status !== "UPLOADING") {
+      throw new HttpError(
+        400,
+        "INVALID_STATUS_TRANSITION",
+        `Cannot add chunks to recording in status ${recording.status}`,
+      );
+    }
+
+    const body = await validateBody(request, (b) => {
+      const obj = b as Record<string, unknown>;
+      const chunkIndex = requireNumber(obj, "chunkIndex");
+      const startMs = requireNumber(obj, "startMs");
+      const endMs = requireNumber(obj, "endMs");
+      if (chunkIndex < 0 || startMs < 0 || endMs <= startMs) {
+        throw new HttpError(400, "VALIDATION_ERROR", "Invalid chunk range");
+      }
+      return {
+        chunkIndex,
+        startMs,
+        endMs,
+        fileName: requireString(obj, "fileName"),
+        sha256: typeof obj.sha256 === "string" ? obj.sha256 : null,
+      };
+    });
+
+    const ext = body.fileName.toLowerCase().split(".").pop() ?? "bin";
+    const r2Key = R2_KEYS.chunk(
+      org.orgId,
+      recordingId,
+      body.chunkIndex,
+      body.startMs,
+      body.endMs,
+      ext,
+    );
+
+    await env.DB.batch([
+      env.DB.prepare(
+        "DELETE FROM recording_chunks WHERE recordingId = ? AND chunkIndex = ?",
+      ).bind(recordingId, body.chunkIndex),
+      env.DB.prepare(
+        `INSERT INTO recording_chunks (id, recordingId, chunkIndex, startMs, endMs, r2Key, sha256, createdAt)
+         VALUES (?, ?, ?, ?, ?, ?, ?, datetime('now'))`,
+      ).bind(
+        generateId(ID_PREFIX.chunk),
+        recordingId,
+        body.chunkIndex,
+        body.startMs,
+        body.endMs,
+        r2Key,
+        body.sha256,
+      ),
+    ]);
+
+    const client = createR2Client(env);
+    const { url, headers } = await presignPutUrl(
+      client,
+      "r2-raw-audio",
+      r2Key,
+      PRESIGN_TTL.UPLOAD_DEFAULT_SECONDS,
+      recording.mimeType,
+      body.sha256 ? (hexToBase64(body.sha256) ?? undefined) : undefined,
+    );
+
+    return jsonResponse({ method: "PUT", url, headers, r2Key }, 201);
+  },
+) as RequestHandler;
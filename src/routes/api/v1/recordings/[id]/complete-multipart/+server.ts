@@ -7,7 +7,9 @@ import { HttpError, jsonResponse } from "$lib/utils/response.js";
 import {
   createR2Client,
   completeMultipartUpload,
+  listParts,
 } from "$lib/server/r2/presign.js";
+import { compositeSha256 } from "$lib/server/r2/digest.js";
 import type { RequestHandler } from "./$types.js";
 
 export const POST: RequestHandler = withAuth(
@@ -34,6 +36,7 @@ export const POST: RequestHandler = withAuth(
       const parts = obj.parts as Array<{
         partNumber: number;
         eTag: string;
+        checksumSha256?: string;
       }>;
       if (!Array.isArray(parts) || parts.length === 0) {
         throw new HttpError(400, "VALIDATION_ERROR", "parts array required");
@@ -43,11 +46,32 @@ export const POST: RequestHandler = withAuth(
         parts: parts.map((p) => ({
           partNumber: p.partNumber,
           eTag: p.eTag,
+          checksumSha256:
+            typeof p.checksumSha256 === "string" ? p.checksumSha256 : undefined,
         })),
       };
     });
 
     const client = createR2Client(env);
+
+    // Storage checked each part against its checksum; their composite is what
+    // completion verifies the assembled object by, without reading it back.
+    // It is built from the checksums storage lists for the parts, read before
+    // completion discards them, so it doesn't rest on the client's word.
+    const stored = await listParts(
+      client,
+      "r2-raw-audio",
+      recording.rawR2Key,
+      body.uploadId,
+    );
+    const multipartSha256 = await compositeSha256(
+      body.parts.map((part) => ({
+        partNumber: part.partNumber,
+        checksumSha256: stored.find((s) => s.partNumber === part.partNumber)
+          ?.checksumSha256,
+      })),
+    );
+
     await completeMultipartUpload(
       client,
       "r2-raw-audio",
@@ -56,6 +80,12 @@ export const POST: RequestHandler = withAuth(
       body.parts,
     );
 
+    await env.DB.prepare(
+      "UPDATE recordings SET multipartSha256 = ?, updatedAt = datetime('now') WHERE id = ? AND orgId = ?",
+    )
+      .bind(multipartSha256, recordingId, org.orgId)
+      .run();
+
     return jsonResponse({ ok: true });
   },
 ) as RequestHandler;
@@ -1,5 +1,6 @@
 import { withAuth } from "$lib/server/api/middleware.js";
 import { HttpError, jsonResponse } from "$lib/utils/response.js";
+import { objectSha256 } from "$lib/server/r2/digest.js";
 import type { RequestHandler } from "./$types.js";
 
 export const POST: RequestHandler = withAuth(
@@ -8,18 +9,40 @@ export const POST: RequestHandler = withAuth(
     const recordingId = params.id;
 
     const recording = await env.DB.prepare(
-      "SELECT id, status FROM recordings WHERE id = ? AND orgId = ?",
+      "SELECT id, status, rawR2Key, multipartSha256 FROM recordings WHERE id = ? AND orgId = ?",
     )
       .bind(recordingId, org.orgId)
-      .first<{ id: string; status: string }>();
+      .first<{
+        id: string;
+        status: string;
+        rawR2Key: string;
+        multipartSha256: string | null;
+      }>();
 
     if (!recording) {
       throw new HttpError(404, "NOT_FOUND", "Recording not found");
     }
 
-    // This is an optional notification. R2 Event Notification is the primary trigger.
-    // Only update if still in UPLOADING state.
-    if (recording.status === "UPLOADING") {
+    // Record what storage actually holds, for the uploader to verify against.
+    // A missing object clears it, so the upload is seen as not arrived.
+    const object = await env.R2_RAW_AUDIO.head(recording.rawR2Key);
+    const storedSha256 = object
+      ? await objectSha256(
+          env.R2_RAW_AUDIO,
+          recording.rawR2Key,
+          object,
+          recording.multipartSha256,
+        )
+      : null;
+    await env.DB.prepare(
+      "UPDATE recordings SET storedSizeBytes = ?, storedSha256 = ?, updatedAt = datetime('now') WHERE id = ? AND orgId = ?",
+    )
+      .bind(object?.size ?? null, storedSha256, recordingId, org.orgId)
+      .run();
+
+    // R2 Event Notification is the primary trigger; this only catches up a
+    // recording still in UPLOADING whose object has arrived.
+    if (object && recording.status === "UPLOADING") {
       await env.DB.prepare(
         "UPDATE recordings SET status = 'UPLOADED', updatedAt = datetime('now') WHERE id = ? AND orgId = ?",
       )
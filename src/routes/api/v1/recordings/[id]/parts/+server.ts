@@ -0,0 +1,58 @@
+import { withAuth } from "$lib/server/api/middleware.js";
+import { HttpError, jsonResponse } from "$lib/utils/response.js";
+import { createR2Client, listParts } from "$lib/server/r2/presign.js";
+import type { RequestHandler } from "./$types.js";
+
+/**
+ * Parts already stored for a multipart upload, so a resuming client can
+ * recover ETags it lost and skip parts that made it.
+ */
+export const GET: RequestHandler = withAuth(
+  async ({ request, platform, org, params }) => {
+    const env = platform.env;
+    const recordingId = params.id;
+
+    const uploadId = new URL(request.url).searchParams.get("uploadId");
+    if (!uploadId) {
+      throw new HttpError(
+        400,
+        "VALIDATION_ERROR",
+        "Query parameter 'uploadId' is required",
+      );
+    }
+
+    const recording = await env.DB.prepare(
+      "SELECT id, rawR2Key FROM recordings WHERE id = ? AND orgId = ? AND status = 'UPLOADING'",
+    )
+      .bind(recordingId, org.orgId)
+      .first<{ id: string; rawR2Key: string }>();
+
+    if (!recording) {
+      throw new HttpError(
+        404,
+        "NOT_FOUND",
+        "Recording not found or not in UPLOADING state",
+      );
+    }
+
+    const client = createR2Client(env);
+    try {
+      const parts = await listParts(
+        client,
+        "r2-raw-audio",
+        recording.rawR2Key,
+        uploadId,
+      );
+      return jsonResponse({ uploadId, parts });
+    } catch (err) {
+      if (err instanceof Error && err.name === "NoSuchUpload") {
+        throw new HttpError(
+          404,
+          "NOT_FOUND",
+          "Multipart upload not found; it was completed or aborted",
+        );
+      }
+      throw err;
+    }
+  },
+) as RequestHandler;
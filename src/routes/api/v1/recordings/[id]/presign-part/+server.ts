@@ -33,19 +33,22 @@ export const POST: RequestHandler = withAuth(
       return {
         uploadId: requireString(obj, "uploadId"),
         partNumber: requireNumber(obj, "partNumber"),
+        checksumSha256:
+          typeof obj.checksumSha256 === "string" ? obj.checksumSha256 : undefined,
       };
     });
 
     const client = createR2Client(env);
-    const url = await presignPartUrl(
+    const { url, headers } = await presignPartUrl(
       client,
       "r2-raw-audio",
       recording.rawR2Key,
       body.uploadId,
       body.partNumber,
       PRESIGN_TTL.UPLOAD_DEFAULT_SECONDS,
+      body.checksumSha256,
     );
 
-    return jsonResponse({ url, partNumber: body.partNumber });
+    return jsonResponse({ url, partNumber: body.partNumber, headers });
   },
 ) as RequestHandler;
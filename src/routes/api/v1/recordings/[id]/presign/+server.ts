@@ -29,7 +29,9 @@ export const POST: RequestHandler = withAuth(
       throw new HttpError(404, "NOT_FOUND", "Recording not found");
     }
 
-    if (recording.status !== "REGISTERED" && recording.status !== "UPLOADING") {
+    // UPLOADED is allowed so an upload that failed verification can be
+    // replaced before processing picks it up
+    if (!["REGISTERED", "UPLOADING", "UPLOADED"].includes(recording.status)) {
       throw new HttpError(
         400,
         "INVALID_STATUS_TRANSITION",
@@ -41,6 +43,10 @@ export const POST: RequestHandler = withAuth(
       const obj = b as Record<string, unknown>;
       return {
         multipart: obj.multipart === true,
+        checksumSha256:
+          typeof obj.checksumSha256 === "string" ? obj.checksumSha256 : undefined,
+        checksumAlgorithm:
+          obj.checksumAlgorithm === "SHA256" ? ("SHA256" as const) : undefined,
         expiresInSeconds:
           typeof obj.expiresInSeconds === "number"
             ? Math.min(
@@ -59,11 +65,13 @@ export const POST: RequestHandler = withAuth(
         "r2-raw-audio",
         recording.rawR2Key,
         recording.mimeType,
+        body.checksumAlgorithm,
       );
 
-      // Update status to UPLOADING
+      // Update status to UPLOADING; the composite of an earlier multipart
+      // upload no longer describes what will be stored
       await env.DB.prepare(
-        "UPDATE recordings SET status = 'UPLOADING', updatedAt = datetime('now') WHERE id = ? AND orgId = ?",
+        "UPDATE recordings SET status = 'UPLOADING', multipartSha256 = NULL, updatedAt = datetime('now') WHERE id = ? AND orgId = ?",
       )
         .bind(recordingId, org.orgId)
         .run();
@@ -81,11 +89,12 @@ export const POST: RequestHandler = withAuth(
       recording.rawR2Key,
       body.expiresInSeconds,
       recording.mimeType,
+      body.checksumSha256,
     );
 
     // Update status to UPLOADING
     await env.DB.prepare(
-      "UPDATE recordings SET status = 'UPLOADING', updatedAt = datetime('now') WHERE id = ? AND orgId = ?",
+      "UPDATE recordings SET status = 'UPLOADING', multipartSha256 = NULL, updatedAt = datetime('now') WHERE id = ? AND orgId = ?",
     )
       .bind(recordingId, org.orgId)
       .run();
@@ -0,0 +1,88 @@
+import { withAuth, validateBody } from "$lib/server/api/middleware.js";
+import { HttpError, jsonResponse } from "$lib/utils/response.js";
+import { waveformKey } from "$lib/server/r2/keys.js";
+import type { RequestHandler } from "./$types.js";
+
+/** Longest peaks array accepted: 2000 min/max pairs, as the desktop app produces */
+const MAX_PEAK_VALUES = 4000;
+
+async function requireRecording(
+  db: D1Database,
+  recordingId: string,
+  orgId: string,
+): Promise<void> {
+  const recording = await db
+    .prepare("SELECT id FROM recordings WHERE id = ? AND orgId = ?")
+    .bind(recordingId, orgId)
+    .first<{ id: string }>();
+
+  if (!recording) {
+    throw new HttpError(404, "NOT_FOUND", "Recording not found");
+  }
+}
+
+export const GET: RequestHandler = withAuth(
+  async ({ platform, org, params }) => {
+    const env = platform.env;
+    await requireRecording(env.DB, params.id, org.orgId);
+
+    const obj = await env.R2_ARTIFACTS.get(waveformKey(org.orgId, params.id));
+    if (!obj) {
+      throw new HttpError(404, "NOT_FOUND", "No waveform for this recording");
+    }
+
+    return jsonResponse(await obj.json());
+  },
+) as RequestHandler;
+
+/**
+ * Store waveform peaks computed by the desktop app. They live with the
+ * artifacts rather than the raw audio, whose bucket events start processing.
+ */
+export const PUT: RequestHandler = withAuth(
+  async ({ request, platform, org, params }) => {
+    const env = platform.env;
+    await requireRecording(env.DB, params.id, org.orgId);
+
+    const waveform = await validateBody(request, (b) => {
+      const obj = b as Record<string, unknown>;
+      const peaks = obj.peaks;
+      if (
+        !Array.isArray(peaks) ||
+        peaks.length > MAX_PEAK_VALUES ||
+        peaks.length % 2 !== 0 ||
+        !peaks.every((p) => Number.isInteger(p) && p >= -127 && p <= 127)
+      ) {
+        throw new HttpError(
+          400,
+          "VALIDATION_ERROR",
+          `Field 'peaks' must be an even-length array of at most ${MAX_PEAK_VALUES} integers in -127..127`,
+        );
+      }
+      for (const field of ["version", "sampleRate", "samplesPerPeak", "durationMs"]) {
+        if (typeof obj[field] !== "number") {
+          throw new HttpError(
+            400,
+            "VALIDATION_ERROR",
+            `Field '${field}' is required and must be a number`,
+          );
+        }
+      }
+      return {
+        version: obj.version as number,
+        sampleRate: obj.sampleRate as number,
+        samplesPerPeak: obj.samplesPerPeak as number,
+        durationMs: obj.durationMs as number,
+        peaks: peaks as number[],
+      };
+    });
+
+    await env.R2_ARTIFACTS.put(
+      waveformKey(org.orgId, params.id),
+      JSON.stringify(waveform),
+      { httpMetadata: { contentType: "application/json" } },
+    );
+
+    return jsonResponse({ ok: true });
+  },
+) as RequestHandler;
@@ -36,6 +36,14 @@ export const POST: RequestHandler = withAuth(async ({ request, platform, org })
         recorderFileCreatedAt: (f.recorderFileCreatedAt as string) || null,
         sizeBytes: f.sizeBytes as number,
         sha256: requireString(f, "sha256"),
+        mimeType: typeof f.mimeType === "string" && f.mimeType.length > 0 ? f.mimeType : null,
+        durationMs: typeof f.durationMs === "number" ? Math.round(f.durationMs) : null,
+        sequenceId: typeof f.sequenceId === "string" && f.sequenceId.length > 0 ? f.sequenceId : null,
+        sequenceIndex: typeof f.sequenceIndex === "number" ? f.sequenceIndex : null,
+        sequenceCount: typeof f.sequenceCount === "number" ? f.sequenceCount : null,
+        channelGroupId: typeof f.channelGroupId === "string" && f.channelGroupId.length > 0 ? f.channelGroupId : null,
+        channelIndex: typeof f.channelIndex === "number" ? f.channelIndex : null,
+        channelCount: typeof f.channelCount === "number" ? f.channelCount : null,
       })),
     };
   });
@@ -117,7 +125,8 @@ export const POST: RequestHandler = withAuth(async ({ request, platform, org })
       );
 
       const ext = file.originalFileName.toLowerCase().split(".").pop();
-      const mimeType =
+      // Prefer the MIME type probed by the client; fall back to the extension
+      const mimeType = file.mimeType ?? (
         ext === "wma"
           ? "audio/x-ms-wma"
           : ext === "wav"
@@ -130,12 +139,12 @@ export const POST: RequestHandler = withAuth(async ({ request, platform, org })
                   ? "audio/flac"
                   : ext === "ogg"
                     ? "audio/ogg"
-                    : "application/octet-stream";
+                    : "application/octet-stream");
       const needsConversion = mimeType === "audio/x-ms-wma" ? 1 : 0;
 
       await env.DB.prepare(
-        `INSERT INTO recordings (id, orgId, deviceId, importBatchId, originalFileName, recorderFileCreatedAt, sizeBytes, sha256, mimeType, needsConversion, rawR2Key, status, createdAt, updatedAt)
-         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, 'REGISTERED', datetime('now'), datetime('now'))`,
+        `INSERT INTO recordings (id, orgId, deviceId, importBatchId, originalFileName, recorderFileCreatedAt, sizeBytes, sha256, durationMs, mimeType, needsConversion, rawR2Key, sequenceId, sequenceIndex, sequenceCount, channelGroupId, channelIndex, channelCount, status, createdAt, updatedAt)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, 'REGISTERED', datetime('now'), datetime('now'))`,
       )
         .bind(
           recordingId,
@@ -146,9 +155,16 @@ export const POST: RequestHandler = withAuth(async ({ request, platform, org })
           file.recorderFileCreatedAt,
           file.sizeBytes,
           file.sha256,
+          file.durationMs,
           mimeType,
           needsConversion,
           rawR2Key,
+          file.sequenceId,
+          file.sequenceIndex,
+          file.sequenceCount,
+          file.channelGroupId,
+          file.channelIndex,
+          file.channelCount,
         )
         .run();
 
@@ -0,0 +1,47 @@
+import { describe, it, expect } from "vitest";
+import { compositeSha256, toHex } from "../../../src/lib/server/r2/digest.js";
+
+describe("toHex", () => {
+  it("encodes bytes as zero-padded lowercase hex", () => {
+    expect(toHex(new Uint8Array([0x00, 0x0f, 0xab, 0xff]).buffer)).toBe("000fabff");
+  });
+
+  it("encodes an empty buffer as an empty string", () => {
+    expect(toHex(new ArrayBuffer(0))).toBe("");
+  });
+});
+
+describe("compositeSha256", () => {
+  // SHA-256 of "part one" and "part two"
+  const partOne = "/tfwXBC8ldWX5vgQMijEwQeYtfdyFfcYqFRTTaVj3J4=";
+  const partTwo = "6laDy6WANfTzuTcCPLpwTwvkdmuspd1N7uRgvdEJF0E=";
+  const expected =
+    "65c7f326f1d6017f6f3bb7cf280f57514e5db59fa69ab005c602834258e50b69-2";
+
+  it("hashes the part digests in part order", async () => {
+    expect(
+      await compositeSha256([
+        { partNumber: 2, checksumSha256: partTwo },
+        { partNumber: 1, checksumSha256: partOne },
+      ]),
+    ).toBe(expected);
+  });
+
+  it("is null when a part has no checksum", async () => {
+    expect(
+      await compositeSha256([
+        { partNumber: 1, checksumSha256: partOne },
+        { partNumber: 2 },
+      ]),
+    ).toBeNull();
+  });
+
+  it("is null when a checksum is not a SHA-256", async () => {
+    expect(
+      await compositeSha256([{ partNumber: 1, checksumSha256: "not base64!" }]),
+    ).toBeNull();
+    expect(
+      await compositeSha256([{ partNumber: 1, checksumSha256: "AAAA" }]),
+    ).toBeNull();
+  });
+});
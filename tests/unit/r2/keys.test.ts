@@ -2,6 +2,7 @@ import { describe, it, expect } from "vitest";
 import {
   rawKey,
   chunkKey,
+  waveformKey,
   artifactKey,
   workshopExportKey,
   parseRecordingIdFromKey,
@@ -39,6 +40,14 @@ describe("R2 key construction", () => {
     });
   });
 
+  describe("waveformKey", () => {
+    it("constructs correct path for waveform peaks", () => {
+      expect(waveformKey(orgId, recordingId)).toBe(
+        "org/org-abc123/recording/recfile-def456/waveform.json",
+      );
+    });
+  });
+
   describe("artifactKey", () => {
     it("constructs correct path for transcript artifact", () => {
       const runId = "run-789";